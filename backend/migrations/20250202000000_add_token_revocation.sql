@@ -0,0 +1,16 @@
+-- Access token revocation
+-- Individually revoked tokens are kept until they would have expired anyway,
+-- after which they are purged. tokens_valid_after rejects every token an admin
+-- was issued before that instant ("sign out everywhere").
+
+CREATE TABLE revoked_tokens (
+    jti UUID PRIMARY KEY,
+    admin_id UUID REFERENCES admins(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_revoked_tokens_expires_at ON revoked_tokens(expires_at);
+
+ALTER TABLE admins
+ADD COLUMN IF NOT EXISTS tokens_valid_after TIMESTAMPTZ;
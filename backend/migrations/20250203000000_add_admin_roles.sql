@@ -0,0 +1,24 @@
+-- Role-based access control for multiple admin accounts
+-- Existing accounts could already do everything, so they become owners.
+-- New accounts default to the least privileged role.
+
+ALTER TABLE admins
+ADD COLUMN IF NOT EXISTS role VARCHAR(20) NOT NULL DEFAULT 'owner',
+ADD COLUMN IF NOT EXISTS disabled_at TIMESTAMPTZ;
+
+ALTER TABLE admins ALTER COLUMN role SET DEFAULT 'viewer';
+
+ALTER TABLE admins
+ADD CONSTRAINT admins_role_check CHECK (role IN ('owner', 'editor', 'author', 'viewer'));
+
+-- One-time invitations created by an owner
+CREATE TABLE admin_invitations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    role VARCHAR(20) NOT NULL CHECK (role IN ('owner', 'editor', 'author', 'viewer')),
+    invited_by UUID REFERENCES admins(id) ON DELETE SET NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    accepted_at TIMESTAMPTZ,
+    accepted_by UUID REFERENCES admins(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
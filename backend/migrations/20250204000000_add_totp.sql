@@ -0,0 +1,20 @@
+-- Optional TOTP (RFC 6238) two-factor authentication
+-- totp_secret is set during enrollment; 2FA is only enforced once
+-- totp_enabled_at is set by confirming a code. totp_last_used_step stops a
+-- code from being replayed within its validity window.
+
+ALTER TABLE admins
+ADD COLUMN IF NOT EXISTS totp_secret VARCHAR(64),
+ADD COLUMN IF NOT EXISTS totp_enabled_at TIMESTAMPTZ,
+ADD COLUMN IF NOT EXISTS totp_last_used_step BIGINT;
+
+-- Single-use recovery codes (hashed)
+CREATE TABLE admin_recovery_codes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    admin_id UUID NOT NULL REFERENCES admins(id) ON DELETE CASCADE,
+    code_hash VARCHAR(64) NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_recovery_codes_admin_id ON admin_recovery_codes(admin_id);
@@ -0,0 +1,19 @@
+-- Brute-force protection for /api/auth/login
+-- Consecutive failures lock an account until locked_until; every attempt is
+-- recorded in login_events.
+
+ALTER TABLE admins
+ADD COLUMN IF NOT EXISTS failed_login_attempts INT NOT NULL DEFAULT 0,
+ADD COLUMN IF NOT EXISTS locked_until TIMESTAMPTZ;
+
+CREATE TABLE login_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    username VARCHAR(255) NOT NULL,
+    admin_id UUID REFERENCES admins(id) ON DELETE SET NULL,
+    ip VARCHAR(45),
+    outcome VARCHAR(30) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_login_events_username ON login_events(username, created_at);
+CREATE INDEX idx_login_events_ip ON login_events(ip, created_at);
@@ -0,0 +1,7 @@
+-- Device details for listing active sessions.
+-- Each refresh token records the client that obtained it, so the newest token
+-- of a family describes where that session was last seen.
+
+ALTER TABLE refresh_tokens
+    ADD COLUMN IF NOT EXISTS user_agent VARCHAR(512),
+    ADD COLUMN IF NOT EXISTS ip VARCHAR(45);
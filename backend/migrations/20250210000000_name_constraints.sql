@@ -0,0 +1,37 @@
+-- Explicit names for the constraints that error/database.rs maps to error
+-- codes, instead of the ones Postgres generated from the column lists.
+-- Renames are skipped when the generated name is not there, so this can be
+-- re-run against a partially migrated database.
+
+DO $$
+DECLARE
+    r RECORD;
+BEGIN
+    FOR r IN
+        SELECT * FROM (VALUES
+            ('novels', 'novels_slug_key', 'novels_slug_unique'),
+            ('novel_chapters', 'novel_chapters_novel_id_chapter_number_key', 'novel_chapters_number_unique'),
+            ('blog_posts', 'blog_posts_slug_key', 'blog_posts_slug_unique'),
+            ('apps', 'apps_slug_key', 'apps_slug_unique'),
+            ('admins', 'admins_username_key', 'admins_username_unique'),
+            ('admin_identities', 'admin_identities_issuer_subject_key', 'admin_identities_subject_unique'),
+            ('novel_chapters', 'novel_chapters_novel_id_fkey', 'novel_chapters_novel_fk'),
+            ('novel_relations', 'novel_relations_novel_id_fkey', 'novel_relations_novel_fk'),
+            ('novel_relations', 'novel_relations_related_novel_id_fkey', 'novel_relations_related_novel_fk'),
+            ('app_distributions', 'app_distributions_app_id_fkey', 'app_distributions_app_fk'),
+            ('api_tokens', 'api_tokens_admin_id_fkey', 'api_tokens_admin_fk'),
+            ('admin_identities', 'admin_identities_admin_id_fkey', 'admin_identities_admin_fk'),
+            ('admin_invitations', 'admin_invitations_role_check', 'admin_invitations_role_valid')
+        ) AS renames (table_name, old_name, new_name)
+    LOOP
+        IF EXISTS (
+            SELECT 1 FROM pg_constraint
+            WHERE conrelid = r.table_name::regclass AND conname = r.old_name
+        ) THEN
+            EXECUTE format(
+                'ALTER TABLE %I RENAME CONSTRAINT %I TO %I',
+                r.table_name, r.old_name, r.new_name
+            );
+        END IF;
+    END LOOP;
+END $$;
@@ -0,0 +1,351 @@
+//! JWT signing and verification keys.
+//!
+//! Tokens are signed with HS256 and `JWT_SECRET` unless an RSA or Ed25519 key
+//! pair is configured, in which case they are signed with RS256 or EdDSA and
+//! carry the key's `kid`. Public keys of earlier key pairs can be kept around
+//! so their tokens stay valid during a rotation. Every public key is served as
+//! a JWKS so other services can verify tokens too.
+
+use anyhow::{anyhow, bail, Context};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{
+    decode, decode_header, encode,
+    errors::{Error as JwtError, ErrorKind},
+    Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
+use rsa::{
+    pkcs8::{Document, ObjectIdentifier, SubjectPublicKeyInfoRef},
+    traits::PublicKeyParts,
+    RsaPublicKey,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, fs};
+
+use crate::{
+    config::{Config, DEFAULT_JWT_SECRET},
+    error::AppError,
+};
+
+const RSA_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
+const ED25519_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.112");
+
+struct VerifyingKey {
+    algorithm: Algorithm,
+    key: DecodingKey,
+    jwk: Value,
+}
+
+pub struct JwtKeys {
+    algorithm: Algorithm,
+    kid: Option<String>,
+    signing_key: EncodingKey,
+    /// Public keys by `kid`, including the current one
+    verifying_keys: HashMap<String, VerifyingKey>,
+    /// Verifies tokens without a `kid`; only set while signing with HS256
+    secret: Option<DecodingKey>,
+}
+
+impl JwtKeys {
+    pub fn from_config(config: &Config) -> anyhow::Result<Self> {
+        let mut verifying_keys = HashMap::new();
+
+        for path in &config.jwt.previous_public_key_files {
+            let (kid, key) = load_public_key(path)?;
+            verifying_keys.insert(kid, key);
+        }
+
+        let Some(private_key_file) = &config.jwt.private_key_file else {
+            if config.jwt.secret == DEFAULT_JWT_SECRET {
+                tracing::warn!("JWT_SECRET is not set; signing tokens with the insecure default");
+            }
+
+            return Ok(Self {
+                algorithm: Algorithm::HS256,
+                kid: None,
+                signing_key: EncodingKey::from_secret(config.jwt.secret.as_bytes()),
+                verifying_keys,
+                secret: Some(DecodingKey::from_secret(config.jwt.secret.as_bytes())),
+            });
+        };
+
+        let public_key_file =
+            config.jwt.public_key_file.as_ref().ok_or_else(|| {
+                anyhow!("JWT_PUBLIC_KEY_FILE is required with JWT_PRIVATE_KEY_FILE")
+            })?;
+
+        let (kid, public_key) = load_public_key(public_key_file)?;
+        let algorithm = public_key.algorithm;
+
+        let private_pem = fs::read(private_key_file)
+            .with_context(|| format!("Failed to read {}", private_key_file))?;
+        let signing_key = match algorithm {
+            Algorithm::RS256 => EncodingKey::from_rsa_pem(&private_pem),
+            _ => EncodingKey::from_ed_pem(&private_pem),
+        }
+        .with_context(|| format!("Invalid private key in {}", private_key_file))?;
+
+        verifying_keys.insert(kid.clone(), public_key);
+
+        let keys = Self {
+            algorithm,
+            kid: Some(kid),
+            signing_key,
+            verifying_keys,
+            secret: None,
+        };
+
+        // Catch a private key that doesn't belong to the public key at startup
+        let probe = keys.encode(&json!({ "exp": i64::MAX }))?;
+        let mut validation = Validation::new(algorithm);
+        validation.set_required_spec_claims(&["exp"]);
+        keys.decode::<Value>(&probe, validation)
+            .map_err(|_| anyhow!("JWT private and public key files don't match"))?;
+
+        tracing::info!(
+            "Signing tokens with {:?} key {}",
+            algorithm,
+            keys.kid.as_deref().unwrap_or_default()
+        );
+
+        Ok(keys)
+    }
+
+    /// Sign claims with the current key
+    pub fn encode<T: Serialize>(&self, claims: &T) -> Result<String, AppError> {
+        let mut header = Header::new(self.algorithm);
+        header.kid = self.kid.clone();
+
+        encode(&header, claims, &self.signing_key)
+            .map_err(|_| AppError::InternalError("Token generation failed".to_string()))
+    }
+
+    /// Verify a token with the key named by its `kid`. The algorithm always
+    /// comes from the key, never from the token header.
+    pub fn decode<T: DeserializeOwned>(
+        &self,
+        token: &str,
+        mut validation: Validation,
+    ) -> Result<T, JwtError> {
+        let header = decode_header(token)?;
+
+        let (algorithm, key) = match &header.kid {
+            Some(kid) => self
+                .verifying_keys
+                .get(kid)
+                .map(|verifying| (verifying.algorithm, &verifying.key)),
+            None => self
+                .secret
+                .as_ref()
+                .map(|secret| (Algorithm::HS256, secret)),
+        }
+        .ok_or_else(|| JwtError::from(ErrorKind::InvalidToken))?;
+
+        validation.algorithms = vec![algorithm];
+
+        Ok(decode::<T>(token, key, &validation)?.claims)
+    }
+
+    /// All public verification keys as a JWK set
+    pub fn jwks(&self) -> Value {
+        let keys: Vec<&Value> = self.verifying_keys.values().map(|key| &key.jwk).collect();
+
+        json!({ "keys": keys })
+    }
+}
+
+/// Load an RSA or Ed25519 public key from a PEM file, returning its RFC 7638
+/// thumbprint as the `kid`
+fn load_public_key(path: &str) -> anyhow::Result<(String, VerifyingKey)> {
+    let pem = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+    let (_, document) =
+        Document::from_pem(&pem).map_err(|e| anyhow!("Invalid PEM in {}: {}", path, e))?;
+    let spki = SubjectPublicKeyInfoRef::try_from(document.as_bytes())
+        .map_err(|e| anyhow!("{} is not a public key: {}", path, e))?;
+
+    let (algorithm, key, members) = if spki.algorithm.oid == RSA_OID {
+        let public_key = RsaPublicKey::try_from(spki)
+            .map_err(|e| anyhow!("Invalid RSA key in {}: {}", path, e))?;
+        let n = public_key.n().to_bytes_be();
+        let e = public_key.e().to_bytes_be();
+
+        (
+            Algorithm::RS256,
+            DecodingKey::from_rsa_raw_components(&n, &e),
+            // Members in lexicographic order, as the thumbprint requires
+            json!({
+                "e": URL_SAFE_NO_PAD.encode(&e),
+                "kty": "RSA",
+                "n": URL_SAFE_NO_PAD.encode(&n),
+            }),
+        )
+    } else if spki.algorithm.oid == ED25519_OID {
+        let x = URL_SAFE_NO_PAD.encode(spki.subject_public_key.raw_bytes());
+
+        (
+            Algorithm::EdDSA,
+            DecodingKey::from_ed_components(&x)?,
+            json!({
+                "crv": "Ed25519",
+                "kty": "OKP",
+                "x": x,
+            }),
+        )
+    } else {
+        bail!("{} must be an RSA or Ed25519 public key", path);
+    };
+
+    let kid = URL_SAFE_NO_PAD.encode(Sha256::digest(members.to_string().as_bytes()));
+
+    let mut jwk = members;
+    jwk["kid"] = json!(kid);
+    jwk["use"] = json!("sig");
+    jwk["alg"] = json!(match algorithm {
+        Algorithm::RS256 => "RS256",
+        _ => "EdDSA",
+    });
+
+    Ok((
+        kid,
+        VerifyingKey {
+            algorithm,
+            key,
+            jwk,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::engine::general_purpose::STANDARD;
+    use ring::{
+        rand::SystemRandom,
+        signature::{Ed25519KeyPair, KeyPair},
+    };
+
+    /// Prefix of an Ed25519 SubjectPublicKeyInfo, followed by the 32 key bytes
+    const ED25519_SPKI_PREFIX: [u8; 12] = [
+        0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+    ];
+
+    fn pem(label: &str, der: &[u8]) -> String {
+        let base64 = STANDARD.encode(der);
+        let lines: Vec<&str> = base64
+            .as_bytes()
+            .chunks(64)
+            .map(|line| std::str::from_utf8(line).unwrap())
+            .collect();
+        format!(
+            "-----BEGIN {label}-----\n{}\n-----END {label}-----\n",
+            lines.join("\n")
+        )
+    }
+
+    /// Write a fresh Ed25519 key pair, returning the private and public key files
+    fn key_pair() -> (String, String) {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+        let public_key = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref())
+            .unwrap()
+            .public_key()
+            .as_ref()
+            .to_vec();
+
+        let dir = std::env::temp_dir();
+        let name = uuid::Uuid::new_v4();
+        let private_file = dir.join(format!("{}.key", name));
+        let public_file = dir.join(format!("{}.pub", name));
+        fs::write(&private_file, pem("PRIVATE KEY", pkcs8.as_ref())).unwrap();
+        fs::write(
+            &public_file,
+            pem(
+                "PUBLIC KEY",
+                &[&ED25519_SPKI_PREFIX[..], &public_key].concat(),
+            ),
+        )
+        .unwrap();
+
+        (
+            private_file.to_string_lossy().into_owned(),
+            public_file.to_string_lossy().into_owned(),
+        )
+    }
+
+    fn keys(current: &(String, String), previous: &[&(String, String)]) -> JwtKeys {
+        let mut config = Config::default();
+        config.jwt.private_key_file = Some(current.0.clone());
+        config.jwt.public_key_file = Some(current.1.clone());
+        config.jwt.previous_public_key_files =
+            previous.iter().map(|(_, public)| public.clone()).collect();
+        JwtKeys::from_config(&config).unwrap()
+    }
+
+    fn claims() -> Value {
+        json!({ "sub": "admin", "exp": chrono::Utc::now().timestamp() + 60 })
+    }
+
+    fn verify(keys: &JwtKeys, token: &str) -> Result<Value, JwtError> {
+        keys.decode::<Value>(token, Validation::new(Algorithm::HS256))
+    }
+
+    #[test]
+    fn picks_the_key_named_by_kid() {
+        let old = key_pair();
+        let new = key_pair();
+        let rotated = keys(&new, &[&old]);
+
+        let token = rotated.encode(&claims()).unwrap();
+        assert_eq!(
+            decode_header(&token).unwrap().kid.as_deref(),
+            rotated.kid.as_deref()
+        );
+        assert_eq!(decode_header(&token).unwrap().alg, Algorithm::EdDSA);
+        assert!(verify(&rotated, &token).is_ok());
+
+        // Tokens signed before the rotation stay valid
+        let before = keys(&old, &[]).encode(&claims()).unwrap();
+        assert!(verify(&rotated, &before).is_ok());
+
+        // ...but not the other way round
+        assert!(verify(&keys(&old, &[]), &token).is_err());
+
+        assert_eq!(rotated.jwks()["keys"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn rejects_unknown_kids() {
+        let verifier = keys(&key_pair(), &[]);
+        let stranger = keys(&key_pair(), &[]);
+
+        let token = stranger.encode(&claims()).unwrap();
+        let error = verify(&verifier, &token).unwrap_err();
+        assert_eq!(*error.kind(), ErrorKind::InvalidToken);
+    }
+
+    #[test]
+    fn refuses_hs256_with_a_key_pair() {
+        let current = key_pair();
+        let keys = keys(&current, &[]);
+        let secret = EncodingKey::from_secret(Config::default().jwt.secret.as_bytes());
+
+        // Without a kid there is no secret to fall back to
+        let token = encode(&Header::new(Algorithm::HS256), &claims(), &secret).unwrap();
+        assert!(verify(&keys, &token).is_err());
+
+        // With the current kid, the key's algorithm wins over the header's
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = keys.kid.clone();
+        let token = encode(&header, &claims(), &secret).unwrap();
+        assert!(verify(&keys, &token).is_err());
+    }
+
+    #[test]
+    fn hs256_without_a_key_pair() {
+        let keys = JwtKeys::from_config(&Config::default()).unwrap();
+        let token = keys.encode(&claims()).unwrap();
+
+        assert_eq!(decode_header(&token).unwrap().kid, None);
+        assert!(verify(&keys, &token).is_ok());
+    }
+}
@@ -0,0 +1,6 @@
+pub mod keys;
+pub mod oidc;
+pub mod revocation;
+pub mod throttle;
+pub mod tokens;
+pub mod totp;
@@ -0,0 +1,251 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+/// How long a snapshot of the revocation tables is trusted before reloading.
+/// Revocations made by this instance are applied to the cache immediately;
+/// this only bounds how stale revocations made elsewhere can be.
+const RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+struct Snapshot {
+    /// Revoked token ids and when the token would have expired anyway
+    revoked: HashMap<Uuid, DateTime<Utc>>,
+    /// Per-admin cutoff: tokens issued before this instant are rejected
+    cutoffs: HashMap<Uuid, DateTime<Utc>>,
+    loaded_at: Option<Instant>,
+}
+
+impl Snapshot {
+    fn is_fresh(&self) -> bool {
+        self.loaded_at
+            .is_some_and(|loaded_at| loaded_at.elapsed() < RELOAD_INTERVAL)
+    }
+}
+
+/// In-memory cache over `revoked_tokens` and `admins.tokens_valid_after`,
+/// so that checking a token doesn't cost a database round trip per request.
+#[derive(Clone)]
+pub struct RevocationList {
+    pool: PgPool,
+    snapshot: Arc<RwLock<Snapshot>>,
+}
+
+impl RevocationList {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            snapshot: Arc::new(RwLock::new(Snapshot::default())),
+        }
+    }
+
+    /// Check whether a token with the given id, subject and issue time was revoked
+    pub async fn is_revoked(&self, jti: Uuid, admin_id: Uuid, issued_at: f64) -> bool {
+        self.reload_if_stale().await;
+
+        let snapshot = self.snapshot.read().await;
+
+        if snapshot.revoked.contains_key(&jti) {
+            return true;
+        }
+
+        snapshot
+            .cutoffs
+            .get(&admin_id)
+            // Compared to the millisecond; tokens issued in the cutoff's millisecond
+            // are rejected too, as are older tokens whose `iat` is a whole second
+            .is_some_and(|cutoff| (issued_at * 1000.0).round() as i64 <= cutoff.timestamp_millis())
+    }
+
+    /// Revoke a single token until its natural expiry
+    pub async fn revoke(
+        &self,
+        jti: Uuid,
+        admin_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO revoked_tokens (jti, admin_id, expires_at)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (jti) DO NOTHING",
+        )
+        .bind(jti)
+        .bind(admin_id)
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        self.snapshot.write().await.revoked.insert(jti, expires_at);
+
+        Ok(())
+    }
+
+    /// Revoke every token issued to an admin before `before`, including
+    /// refresh tokens, so the sessions can't be renewed either
+    pub async fn revoke_issued_before(
+        &self,
+        admin_id: Uuid,
+        before: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        self.set_cutoff(admin_id, before, true).await
+    }
+
+    /// Reject access tokens issued to an admin before `before`, but let their
+    /// sessions refresh. Used when claims such as the role go stale.
+    pub async fn expire_access_tokens(
+        &self,
+        admin_id: Uuid,
+        before: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        self.set_cutoff(admin_id, before, false).await
+    }
+
+    async fn set_cutoff(
+        &self,
+        admin_id: Uuid,
+        before: DateTime<Utc>,
+        include_refresh_tokens: bool,
+    ) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        // Never move an existing cutoff backwards
+        let cutoff: DateTime<Utc> = sqlx::query_scalar(
+            "UPDATE admins
+             SET tokens_valid_after = GREATEST(COALESCE(tokens_valid_after, $2), $2)
+             WHERE id = $1
+             RETURNING tokens_valid_after",
+        )
+        .bind(admin_id)
+        .bind(before)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if include_refresh_tokens {
+            sqlx::query(
+                "UPDATE refresh_tokens SET revoked_at = NOW()
+                 WHERE admin_id = $1 AND created_at < $2 AND revoked_at IS NULL",
+            )
+            .bind(admin_id)
+            .bind(before)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        self.snapshot.write().await.cutoffs.insert(admin_id, cutoff);
+
+        Ok(())
+    }
+
+    /// Delete revocation entries for tokens that have expired on their own
+    pub async fn purge_expired(&self) -> Result<u64, AppError> {
+        let result = sqlx::query("DELETE FROM revoked_tokens WHERE expires_at <= NOW()")
+            .execute(&self.pool)
+            .await?;
+
+        let now = Utc::now();
+        self.snapshot
+            .write()
+            .await
+            .revoked
+            .retain(|_, expires_at| *expires_at > now);
+
+        Ok(result.rows_affected())
+    }
+
+    async fn reload_if_stale(&self) {
+        if self.snapshot.read().await.is_fresh() {
+            return;
+        }
+
+        let mut snapshot = self.snapshot.write().await;
+
+        // Another request may have reloaded while we waited for the lock
+        if snapshot.is_fresh() {
+            return;
+        }
+
+        if let Err(e) = self.load(&mut snapshot).await {
+            // Keep serving the previous snapshot rather than locking everyone out
+            tracing::warn!("Failed to reload token revocation list: {:?}", e);
+        }
+    }
+
+    async fn load(&self, snapshot: &mut Snapshot) -> Result<(), sqlx::Error> {
+        let revoked: Vec<(Uuid, DateTime<Utc>)> =
+            sqlx::query_as("SELECT jti, expires_at FROM revoked_tokens WHERE expires_at > NOW()")
+                .fetch_all(&self.pool)
+                .await?;
+
+        let cutoffs: Vec<(Uuid, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT id, tokens_valid_after FROM admins WHERE tokens_valid_after IS NOT NULL",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        snapshot.revoked = revoked.into_iter().collect();
+        snapshot.cutoffs = cutoffs.into_iter().collect();
+        snapshot.loaded_at = Some(Instant::now());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    use crate::models::auth::issued_at;
+
+    /// A list whose snapshot is fresh, so checks never reach the database
+    async fn list_with_cutoff(admin_id: Uuid, cutoff: DateTime<Utc>) -> RevocationList {
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://127.0.0.1:1/unused")
+            .unwrap();
+        let list = RevocationList::new(pool);
+        {
+            let mut snapshot = list.snapshot.write().await;
+            snapshot.cutoffs.insert(admin_id, cutoff);
+            snapshot.loaded_at = Some(Instant::now());
+        }
+        list
+    }
+
+    #[tokio::test]
+    async fn cutoff_applies_within_its_second() {
+        let admin_id = Uuid::new_v4();
+        let second = Utc.timestamp_opt(1_800_000_000, 0).unwrap();
+        let cutoff = second + chrono::Duration::milliseconds(500);
+        let list = list_with_cutoff(admin_id, cutoff).await;
+
+        let at = |ms| issued_at(second + chrono::Duration::milliseconds(ms));
+
+        // Issued earlier in the same second as the cutoff
+        assert!(list.is_revoked(Uuid::new_v4(), admin_id, at(100)).await);
+        assert!(list.is_revoked(Uuid::new_v4(), admin_id, at(500)).await);
+        // Issued after it, e.g. the tokens handed out by a password change
+        assert!(!list.is_revoked(Uuid::new_v4(), admin_id, at(501)).await);
+        assert!(!list.is_revoked(Uuid::new_v4(), admin_id, at(1_000)).await);
+        // Tokens from before `iat` had milliseconds
+        assert!(
+            list.is_revoked(Uuid::new_v4(), admin_id, 1_800_000_000.0)
+                .await
+        );
+        // Other admins are unaffected
+        assert!(
+            !list
+                .is_revoked(Uuid::new_v4(), Uuid::new_v4(), at(100))
+                .await
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Failed attempts per username allowed before backoff kicks in
+const USERNAME_FREE_ATTEMPTS: u32 = 3;
+
+/// Failed attempts per client IP allowed before backoff kicks in; higher than
+/// per username since several people may share an address
+const IP_FREE_ATTEMPTS: u32 = 10;
+
+/// Entries without a failure for this long are forgotten
+const ENTRY_TTL: Duration = Duration::from_secs(3600);
+
+struct Entry {
+    failures: u32,
+    last_failure: Instant,
+    blocked_until: Instant,
+}
+
+/// Exponential backoff on repeated failures for a single kind of key
+struct Backoff {
+    free_attempts: u32,
+    max_delay: Duration,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl Backoff {
+    fn new(free_attempts: u32, max_delay: Duration) -> Self {
+        Self {
+            free_attempts,
+            max_delay,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn retry_after(&self, key: &str) -> Option<Duration> {
+        let entries = self.entries.lock().unwrap();
+        let remaining = entries
+            .get(key)?
+            .blocked_until
+            .saturating_duration_since(Instant::now());
+
+        (!remaining.is_zero()).then_some(remaining)
+    }
+
+    fn record_failure(&self, key: &str) {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+
+        entries.retain(|_, entry| now.duration_since(entry.last_failure) < ENTRY_TTL);
+
+        let entry = entries.entry(key.to_string()).or_insert(Entry {
+            failures: 0,
+            last_failure: now,
+            blocked_until: now,
+        });
+
+        entry.failures += 1;
+        entry.last_failure = now;
+
+        // 1s, 2s, 4s, ... once the free attempts are used up
+        if entry.failures > self.free_attempts {
+            let exponent = (entry.failures - self.free_attempts - 1).min(20);
+            let delay = Duration::from_secs(1u64 << exponent).min(self.max_delay);
+            entry.blocked_until = now + delay;
+        }
+    }
+
+    fn reset(&self, key: &str) {
+        self.entries.lock().unwrap().remove(key);
+    }
+}
+
+/// In-memory login throttling per username and per client IP.
+/// Unknown usernames are throttled exactly like real ones.
+#[derive(Clone)]
+pub struct LoginThrottle {
+    by_username: Arc<Backoff>,
+    by_ip: Arc<Backoff>,
+}
+
+impl LoginThrottle {
+    pub fn new(max_delay: Duration) -> Self {
+        Self {
+            by_username: Arc::new(Backoff::new(USERNAME_FREE_ATTEMPTS, max_delay)),
+            by_ip: Arc::new(Backoff::new(IP_FREE_ATTEMPTS, max_delay)),
+        }
+    }
+
+    /// How long the caller has to wait before trying again, if at all
+    pub fn retry_after(&self, username: &str, ip: Option<IpAddr>) -> Option<Duration> {
+        let by_username = self.by_username.retry_after(&username_key(username));
+        let by_ip = ip.and_then(|ip| self.by_ip.retry_after(&ip.to_string()));
+
+        by_username.max(by_ip)
+    }
+
+    pub fn record_failure(&self, username: &str, ip: Option<IpAddr>) {
+        self.by_username.record_failure(&username_key(username));
+        if let Some(ip) = ip {
+            self.by_ip.record_failure(&ip.to_string());
+        }
+    }
+
+    /// Clear the username's history; the IP keeps its record so that one valid
+    /// account can't be used to reset an attacker's address
+    pub fn record_success(&self, username: &str) {
+        self.by_username.reset(&username_key(username));
+    }
+}
+
+fn username_key(username: &str) -> String {
+    username.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_DELAY: Duration = Duration::from_secs(60);
+
+    /// Whether the wait is `expected`, less the time the test took to get here
+    fn waits(retry_after: Option<Duration>, expected: u64) -> bool {
+        let expected = Duration::from_secs(expected);
+        retry_after.is_some_and(|wait| wait <= expected && wait > expected - Duration::from_secs(1))
+    }
+
+    #[test]
+    fn free_attempts_are_not_delayed() {
+        let backoff = Backoff::new(3, MAX_DELAY);
+        for _ in 0..3 {
+            backoff.record_failure("admin");
+            assert_eq!(backoff.retry_after("admin"), None);
+        }
+
+        backoff.record_failure("admin");
+        assert!(waits(backoff.retry_after("admin"), 1));
+        assert_eq!(backoff.retry_after("someone-else"), None);
+    }
+
+    #[test]
+    fn delay_doubles_up_to_the_maximum() {
+        let backoff = Backoff::new(0, Duration::from_secs(5));
+        for expected in [1, 2, 4, 5, 5] {
+            backoff.record_failure("admin");
+            assert!(
+                waits(backoff.retry_after("admin"), expected),
+                "expected {}s, got {:?}",
+                expected,
+                backoff.retry_after("admin")
+            );
+        }
+    }
+
+    #[test]
+    fn success_resets_the_username_but_not_the_ip() {
+        let throttle = LoginThrottle::new(MAX_DELAY);
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+
+        for _ in 0..IP_FREE_ATTEMPTS + 1 {
+            throttle.record_failure("Admin ", Some(ip));
+        }
+        assert!(throttle.retry_after("admin", None).is_some());
+        assert!(throttle.retry_after("other", Some(ip)).is_some());
+
+        throttle.record_success("admin");
+        assert_eq!(throttle.retry_after("admin", None), None);
+        assert!(throttle.retry_after("admin", Some(ip)).is_some());
+        assert_eq!(
+            throttle.retry_after("admin", Some("203.0.113.8".parse().unwrap())),
+            None
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, TimeZone, Utc};
+use jsonwebtoken::Validation;
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+use super::keys::JwtKeys;
+use crate::{
+    config::Config,
+    error::AppError,
+    models::{auth::issued_at, Admin, Claims, MfaClaims},
+};
+
+/// Audience of "mfa pending" tokens. Access token validation rejects any token
+/// carrying an audience, so these can't be used as access tokens.
+const MFA_AUDIENCE: &str = "mfa";
+
+/// How long the second login step may take
+const MFA_TOKEN_EXPIRATION: i64 = 300;
+
+/// Sign a short-lived access token for the given admin and login session
+pub fn encode_access_token(
+    keys: &JwtKeys,
+    config: &Config,
+    admin: &Admin,
+    session_id: uuid::Uuid,
+) -> Result<(String, DateTime<Utc>), AppError> {
+    let claims = Claims::new(
+        admin.id,
+        admin.username.clone(),
+        admin.role,
+        session_id,
+        config.jwt.expiration,
+    );
+
+    let token = keys.encode(&claims)?;
+
+    let expires_at = Utc
+        .timestamp_opt(claims.exp, 0)
+        .single()
+        .ok_or_else(|| AppError::InternalError("Invalid token expiration".to_string()))?;
+
+    Ok((token, expires_at))
+}
+
+/// Sign a short-lived token proving the password step of login succeeded
+pub fn encode_mfa_token(
+    keys: &JwtKeys,
+    admin_id: uuid::Uuid,
+) -> Result<(String, DateTime<Utc>), AppError> {
+    let now = Utc::now();
+    let claims = MfaClaims {
+        sub: admin_id.to_string(),
+        aud: MFA_AUDIENCE.to_string(),
+        jti: uuid::Uuid::new_v4().to_string(),
+        exp: now.timestamp() + MFA_TOKEN_EXPIRATION,
+        iat: issued_at(now),
+    };
+
+    let token = keys.encode(&claims)?;
+
+    let expires_at = Utc
+        .timestamp_opt(claims.exp, 0)
+        .single()
+        .ok_or_else(|| AppError::InternalError("Invalid token expiration".to_string()))?;
+
+    Ok((token, expires_at))
+}
+
+/// Validate an "mfa pending" token
+pub fn decode_mfa_token(keys: &JwtKeys, token: &str) -> Result<MfaClaims, AppError> {
+    let mut validation = Validation::default();
+    validation.set_audience(&[MFA_AUDIENCE]);
+    validation.set_required_spec_claims(&["exp", "aud", "sub"]);
+
+    Ok(keys.decode::<MfaClaims>(token, validation)?)
+}
+
+/// Generate an opaque, URL-safe random token (256 bits)
+pub fn generate_opaque_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Hash an opaque token for storage; only the hash ever reaches the database
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// Marks personal access tokens so they can be told apart from JWTs
+pub const API_TOKEN_PREFIX: &str = "cap_";
+
+/// Generate a new personal access token
+pub fn generate_api_token() -> String {
+    format!("{}{}", API_TOKEN_PREFIX, generate_opaque_token())
+}
@@ -0,0 +1,203 @@
+//! RFC 6238 time-based one-time passwords (SHA-1, 6 digits, 30 second steps),
+//! the variant every authenticator app supports.
+
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use rand::{rngs::OsRng, RngCore};
+use sha1::Sha1;
+
+/// Issuer shown in authenticator apps
+const ISSUER: &str = "0010capacity";
+
+const DIGITS: u32 = 6;
+const STEP_SECONDS: i64 = 30;
+
+/// Accept codes from one step before or after the current one to allow for clock drift
+const ALLOWED_SKEW_STEPS: i64 = 1;
+
+/// Number of recovery codes issued on enrollment
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// Generate a new random 160-bit secret, base32-encoded
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 20];
+    OsRng.fill_bytes(&mut bytes);
+    BASE32_NOPAD.encode(&bytes)
+}
+
+/// Build the `otpauth://` URI that authenticator apps read from a QR code
+pub fn provisioning_uri(secret: &str, account: &str) -> String {
+    let label = format!("{}:{}", ISSUER, account);
+    format!(
+        "otpauth://totp/{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        utf8_percent_encode(&label, NON_ALPHANUMERIC),
+        secret,
+        utf8_percent_encode(ISSUER, NON_ALPHANUMERIC),
+        DIGITS,
+        STEP_SECONDS
+    )
+}
+
+/// Verify a code at the given unix time. Returns the matching time step so the
+/// caller can reject reuse of that step; `None` if the code doesn't match or
+/// belongs to a step at or before `last_used_step`.
+pub fn verify(
+    secret: &str,
+    code: &str,
+    unix_time: i64,
+    last_used_step: Option<i64>,
+) -> Option<i64> {
+    let key = BASE32_NOPAD.decode(secret.as_bytes()).ok()?;
+    let code = code.trim();
+
+    if code.len() != DIGITS as usize || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let current_step = unix_time.div_euclid(STEP_SECONDS);
+
+    (current_step - ALLOWED_SKEW_STEPS..=current_step + ALLOWED_SKEW_STEPS)
+        .filter(|step| last_used_step.is_none_or(|last| *step > last))
+        .find(|step| constant_time_eq(code_at(&key, *step).as_bytes(), code.as_bytes()))
+}
+
+/// Current code for a secret, as an authenticator app would show it
+#[cfg(test)]
+pub fn code_now(secret: &str) -> String {
+    let key = BASE32_NOPAD
+        .decode(secret.as_bytes())
+        .expect("a base32 secret");
+    code_at(
+        &key,
+        chrono::Utc::now().timestamp().div_euclid(STEP_SECONDS),
+    )
+}
+
+/// Generate a human-friendly recovery code, e.g. `k7xq2-m4pzr`
+pub fn generate_recovery_code() -> String {
+    let mut bytes = [0u8; 7];
+    OsRng.fill_bytes(&mut bytes);
+    let encoded = BASE32_NOPAD.encode(&bytes).to_lowercase();
+    format!("{}-{}", &encoded[..5], &encoded[5..10])
+}
+
+/// Normalize user input of a recovery code before hashing
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn code_at(key: &[u8], step: i64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    // Dynamic truncation (RFC 4226 section 5.3)
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(DIGITS),
+        width = DIGITS as usize
+    )
+}
+
+/// Compare secrets without leaking where they first differ
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ASCII "12345678901234567890", the SHA-1 seed of RFC 6238 Appendix B
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn matches_rfc_6238_test_vectors() {
+        let key = BASE32_NOPAD.decode(RFC_SECRET.as_bytes()).unwrap();
+        assert_eq!(key, b"12345678901234567890");
+
+        // The RFC lists 8 digit codes; 6 digit codes are their last six digits
+        for (time, code) in [
+            (59, "94287082"),
+            (1111111109, "07081804"),
+            (1111111111, "14050471"),
+            (1234567890, "89005924"),
+            (2000000000, "69279037"),
+            (20000000000, "65353130"),
+        ] {
+            assert_eq!(
+                code_at(&key, time / STEP_SECONDS),
+                code[2..],
+                "T = {}",
+                time
+            );
+        }
+    }
+
+    #[test]
+    fn verify_allows_one_step_of_skew() {
+        let now = 1234567890;
+        let step = now / STEP_SECONDS;
+
+        assert_eq!(verify(RFC_SECRET, "005924", now, None), Some(step));
+        assert_eq!(verify(RFC_SECRET, " 005924 ", now, None), Some(step));
+
+        let key = BASE32_NOPAD.decode(RFC_SECRET.as_bytes()).unwrap();
+        for offset in [-1, 1] {
+            let code = code_at(&key, step + offset);
+            assert_eq!(verify(RFC_SECRET, &code, now, None), Some(step + offset));
+        }
+        for offset in [-2, 2] {
+            let code = code_at(&key, step + offset);
+            assert_eq!(verify(RFC_SECRET, &code, now, None), None);
+        }
+    }
+
+    #[test]
+    fn verify_rejects_used_steps() {
+        let now = 1234567890;
+        let step = now / STEP_SECONDS;
+
+        assert_eq!(
+            verify(RFC_SECRET, "005924", now, Some(step - 1)),
+            Some(step)
+        );
+        assert_eq!(verify(RFC_SECRET, "005924", now, Some(step)), None);
+        assert_eq!(verify(RFC_SECRET, "005924", now, Some(step + 1)), None);
+    }
+
+    #[test]
+    fn verify_rejects_malformed_codes() {
+        let now = 1234567890;
+        for code in ["", "05924", "0059240", "00592a", "89005924"] {
+            assert_eq!(verify(RFC_SECRET, code, now, None), None, "{:?}", code);
+        }
+        assert_eq!(verify("not base32!", "005924", now, None), None);
+    }
+
+    #[test]
+    fn recovery_codes_normalize_to_the_issued_code() {
+        let code = generate_recovery_code();
+        assert_eq!(code.len(), 11);
+
+        let normalized = normalize_recovery_code(&code);
+        assert_eq!(normalized, code.replace('-', ""));
+        assert_eq!(normalize_recovery_code(&code.to_uppercase()), normalized);
+        assert_eq!(
+            normalize_recovery_code(&format!(" {} ", code.replace('-', " "))),
+            normalized
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use anyhow::{anyhow, bail};
+use chrono::Utc;
+use sqlx::PgPool;
+use std::io::{self, BufRead};
+use validator::Validate;
+
+use crate::{
+    auth::revocation::RevocationList,
+    db::audit::AuditEvent,
+    middleware::request_meta::RequestMeta,
+    models::{Admin, LoginRequest},
+    routes::auth::hash_password,
+};
+
+pub async fn create_admin(
+    pool: &PgPool,
+    username: &str,
+    role: &str,
+    password_stdin: bool,
+) -> anyhow::Result<()> {
+    let password = read_password(password_stdin)?;
+
+    // Same rules as the HTTP endpoints
+    LoginRequest {
+        username: username.to_string(),
+        password: password.clone(),
+    }
+    .validate()?;
+
+    let password_hash = hash_password(&password)?;
+
+    let mut tx = pool.begin().await?;
+
+    let admin = sqlx::query_as::<_, Admin>(
+        "INSERT INTO admins (username, password_hash, role) VALUES ($1, $2, $3)
+         ON CONFLICT (username) DO NOTHING
+         RETURNING id, username, password_hash, role, disabled_at, totp_enabled_at, locked_until, created_at",
+    )
+    .bind(username)
+    .bind(&password_hash)
+    .bind(role)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| anyhow!("Admin {} already exists", username))?;
+
+    AuditEvent::cli("create", "admin", admin.id)
+        .after(&admin)
+        .record(&mut *tx, &RequestMeta::default())
+        .await?;
+    tx.commit().await?;
+
+    println!("Created {} admin {} ({})", role, admin.username, admin.id);
+
+    Ok(())
+}
+
+pub async fn reset_password(
+    pool: &PgPool,
+    username: &str,
+    password_stdin: bool,
+) -> anyhow::Result<()> {
+    let password = read_password(password_stdin)?;
+
+    // Same rules as create-admin and the HTTP endpoints
+    LoginRequest {
+        username: username.to_string(),
+        password: password.clone(),
+    }
+    .validate()?;
+
+    let password_hash = hash_password(&password)?;
+
+    let mut tx = pool.begin().await?;
+
+    let admin_id: uuid::Uuid = sqlx::query_scalar(
+        "UPDATE admins
+         SET password_hash = $2, failed_login_attempts = 0, locked_until = NULL, updated_at = NOW()
+         WHERE username = $1
+         RETURNING id",
+    )
+    .bind(username)
+    .bind(&password_hash)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| anyhow!("Admin {} not found", username))?;
+
+    AuditEvent::cli("reset_password", "admin", admin_id)
+        .record(&mut *tx, &RequestMeta::default())
+        .await?;
+    tx.commit().await?;
+
+    // Running servers pick the cutoff up on their next reload
+    RevocationList::new(pool.clone())
+        .revoke_issued_before(admin_id, Utc::now())
+        .await?;
+
+    println!(
+        "Password reset for {}; all of its sessions were signed out",
+        username
+    );
+
+    Ok(())
+}
+
+/// Prompt for a new password twice without echoing it, or read one line from stdin
+fn read_password(from_stdin: bool) -> anyhow::Result<String> {
+    if from_stdin {
+        let mut line = String::new();
+        io::stdin().lock().read_line(&mut line)?;
+        return Ok(line.trim_end_matches(['\r', '\n']).to_string());
+    }
+
+    let password = rpassword::prompt_password("Password: ")?;
+    let confirmation = rpassword::prompt_password("Repeat password: ")?;
+
+    if password != confirmation {
+        bail!("Passwords don't match");
+    }
+
+    Ok(password)
+}
@@ -0,0 +1,141 @@
+use anyhow::{bail, Context};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{types::Json, PgPool};
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use crate::db::pool::schema_version;
+
+/// Content tables, parents before children. Accounts, tokens and logs are
+/// deliberately left out.
+const TABLES: &[&str] = &[
+    "novels",
+    "novel_chapters",
+    "novel_relations",
+    "blog_posts",
+    "apps",
+    "app_distributions",
+];
+
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct ExportFile {
+    format_version: u32,
+    /// Latest migration of the exporting database; rows match its columns
+    schema_version: i64,
+    exported_at: DateTime<Utc>,
+    tables: BTreeMap<String, Vec<Value>>,
+}
+
+pub async fn export(pool: &PgPool, output: Option<&Path>) -> anyhow::Result<()> {
+    let schema_version = schema_version(pool)
+        .await?
+        .context("Database has no migrations applied")?;
+
+    // One snapshot across all tables, so children never reference missing parents
+    let mut tx = pool.begin().await?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .execute(&mut *tx)
+        .await?;
+
+    let mut tables = BTreeMap::new();
+    for table in TABLES {
+        // Table names only ever come from TABLES
+        let Json(rows): Json<Vec<Value>> = sqlx::query_scalar(&format!(
+            "SELECT COALESCE(json_agg(t ORDER BY t.id), '[]'::json) FROM {} t",
+            table
+        ))
+        .fetch_one(&mut *tx)
+        .await?;
+
+        eprintln!("Exported {} {}", rows.len(), table);
+        tables.insert(table.to_string(), rows);
+    }
+    tx.commit().await?;
+
+    let file = ExportFile {
+        format_version: FORMAT_VERSION,
+        schema_version,
+        exported_at: Utc::now(),
+        tables,
+    };
+
+    match output {
+        Some(path) => {
+            let writer = fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            serde_json::to_writer_pretty(writer, &file)?;
+        }
+        None => serde_json::to_writer_pretty(io::stdout().lock(), &file)?,
+    }
+
+    Ok(())
+}
+
+pub async fn import(pool: &PgPool, input: &Path, dry_run: bool) -> anyhow::Result<()> {
+    let contents =
+        fs::read(input).with_context(|| format!("Failed to read {}", input.display()))?;
+    let mut file: ExportFile = serde_json::from_slice(&contents)
+        .with_context(|| format!("{} is not an export file", input.display()))?;
+
+    if file.format_version != FORMAT_VERSION {
+        bail!("Unsupported export format version {}", file.format_version);
+    }
+
+    // Rows are mapped onto the current columns, so both sides must be on the same schema
+    let current = schema_version(pool).await?.unwrap_or_default();
+    if file.schema_version != current {
+        bail!(
+            "Export is from schema version {} but the database is at {}; migrate both to the same version first",
+            file.schema_version,
+            current
+        );
+    }
+
+    if let Some(unknown) = file
+        .tables
+        .keys()
+        .find(|name| !TABLES.contains(&name.as_str()))
+    {
+        bail!("Export contains unknown table {}", unknown);
+    }
+
+    let mut tx = pool.begin().await?;
+
+    for table in TABLES {
+        let Some(rows) = file.tables.remove(*table) else {
+            continue;
+        };
+        let total = rows.len() as u64;
+
+        // Existing rows, by id or any other unique key, are left as they are
+        let inserted = sqlx::query(&format!(
+            "INSERT INTO {0} SELECT * FROM jsonb_populate_recordset(NULL::{0}, $1)
+             ON CONFLICT DO NOTHING",
+            table
+        ))
+        .bind(Json(rows))
+        .execute(&mut *tx)
+        .await
+        .with_context(|| format!("Failed to import {}", table))?
+        .rows_affected();
+
+        println!(
+            "{}: {} imported, {} skipped",
+            table,
+            inserted,
+            total - inserted
+        );
+    }
+
+    if dry_run {
+        tx.rollback().await?;
+        println!("Dry run, nothing was committed");
+    } else {
+        tx.commit().await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use sqlx::PgPool;
+
+use crate::db::migrations;
+
+pub async fn run(pool: &PgPool, dry_run: bool, allow_destructive: bool) -> anyhow::Result<()> {
+    let pending = migrations::pending(pool).await?;
+
+    if pending.is_empty() {
+        println!("Database is up to date");
+        return Ok(());
+    }
+
+    for migration in &pending {
+        println!(
+            "{} {} {}{}",
+            if dry_run { "Would apply" } else { "Applying" },
+            migration.version,
+            migration.description,
+            if migrations::is_destructive(migration) {
+                " (destructive)"
+            } else {
+                ""
+            }
+        );
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    let applied = migrations::run(pool, allow_destructive).await?;
+    println!("Applied {} migration(s)", applied);
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+//! Command line interface of the `backend` binary. Without a subcommand it
+//! serves the API, as it always has.
+
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+use crate::{config::Config, db::pool};
+
+mod admin;
+mod data;
+mod migrate;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "backend",
+    version,
+    about = "0010capacity backend API and admin tools"
+)]
+pub struct Cli {
+    /// Config file; defaults to config.toml if it exists
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Apply pending migrations and start the API server (the default)
+    Serve(ServeArgs),
+
+    /// Apply pending database migrations
+    Migrate {
+        /// Only list the migrations that would be applied
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Apply migrations that drop data even if the database has data
+        #[arg(long)]
+        allow_destructive_migrations: bool,
+    },
+
+    /// Create an admin account
+    CreateAdmin {
+        username: String,
+
+        #[arg(long, default_value = "owner", value_parser = ["owner", "editor", "author", "viewer"])]
+        role: String,
+
+        /// Read the password from stdin instead of prompting for it
+        #[arg(long)]
+        password_stdin: bool,
+    },
+
+    /// Set a new password for an admin, unlock the account and sign out its sessions
+    ResetPassword {
+        username: String,
+
+        /// Read the password from stdin instead of prompting for it
+        #[arg(long)]
+        password_stdin: bool,
+    },
+
+    /// Export novels, blog posts and apps as JSON
+    Export {
+        /// File to write to instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Import a file written by `export`; rows that already exist are skipped
+    Import {
+        input: PathBuf,
+
+        /// Report what would be imported without committing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Debug, Default, Args)]
+pub struct ServeArgs {
+    /// Don't apply pending migrations on startup
+    #[arg(long)]
+    pub skip_migrations: bool,
+
+    /// Apply migrations that drop data even if the database has data
+    #[arg(long)]
+    pub allow_destructive_migrations: bool,
+}
+
+/// Run any command other than `serve`
+pub async fn run(command: Command, config: &Config) -> anyhow::Result<()> {
+    let pool = pool::connect(&config.database).await?;
+
+    match command {
+        Command::Serve(_) => unreachable!("serve is handled by main"),
+        Command::Migrate {
+            dry_run,
+            allow_destructive_migrations,
+        } => migrate::run(&pool, dry_run, allow_destructive_migrations).await,
+        Command::CreateAdmin {
+            username,
+            role,
+            password_stdin,
+        } => admin::create_admin(&pool, &username, &role, password_stdin).await,
+        Command::ResetPassword {
+            username,
+            password_stdin,
+        } => admin::reset_password(&pool, &username, password_stdin).await,
+        Command::Export { output } => data::export(&pool, output.as_deref()).await,
+        Command::Import { input, dry_run } => data::import(&pool, &input, dry_run).await,
+    }
+}
@@ -0,0 +1,110 @@
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::{
+    error::AppError,
+    middleware::{auth::AuthUser, request_meta::RequestMeta},
+};
+
+/// A change made by an admin, written to `audit_log`.
+///
+/// Record it with the same executor as the change itself so that a change is
+/// never committed without its audit entry.
+pub struct AuditEvent {
+    actor_id: Option<Uuid>,
+    actor_username: String,
+    action: &'static str,
+    entity_type: &'static str,
+    entity_id: String,
+    before: Option<Value>,
+    after: Option<Value>,
+}
+
+impl AuditEvent {
+    pub fn new(
+        actor: &AuthUser,
+        action: &'static str,
+        entity_type: &'static str,
+        entity_id: impl ToString,
+    ) -> Self {
+        Self::with_actor(
+            actor.user_id,
+            &actor.username,
+            action,
+            entity_type,
+            entity_id,
+        )
+    }
+
+    /// Event for an admin who isn't authenticated by the request itself,
+    /// e.g. while completing a sign-in
+    pub fn with_actor(
+        actor_id: Uuid,
+        actor_username: &str,
+        action: &'static str,
+        entity_type: &'static str,
+        entity_id: impl ToString,
+    ) -> Self {
+        Self {
+            actor_id: Some(actor_id),
+            actor_username: actor_username.to_string(),
+            action,
+            entity_type,
+            entity_id: entity_id.to_string(),
+            before: None,
+            after: None,
+        }
+    }
+
+    /// Event for a change made from the command line, with no admin behind it
+    pub fn cli(action: &'static str, entity_type: &'static str, entity_id: impl ToString) -> Self {
+        Self {
+            actor_id: None,
+            actor_username: "cli".to_string(),
+            action,
+            entity_type,
+            entity_id: entity_id.to_string(),
+            before: None,
+            after: None,
+        }
+    }
+
+    /// Snapshot of the entity before the change
+    pub fn before(mut self, snapshot: &impl Serialize) -> Self {
+        self.before = serde_json::to_value(snapshot).ok();
+        self
+    }
+
+    /// Snapshot of the entity after the change
+    pub fn after(mut self, snapshot: &impl Serialize) -> Self {
+        self.after = serde_json::to_value(snapshot).ok();
+        self
+    }
+
+    pub async fn record<'e>(
+        self,
+        executor: impl PgExecutor<'e>,
+        meta: &RequestMeta,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO audit_log
+                 (actor_id, actor_username, action, entity_type, entity_id, before, after, request_id, ip)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        )
+        .bind(self.actor_id)
+        .bind(&self.actor_username)
+        .bind(self.action)
+        .bind(self.entity_type)
+        .bind(&self.entity_id)
+        .bind(&self.before)
+        .bind(&self.after)
+        .bind(&meta.request_id)
+        .bind(meta.ip.map(|ip| ip.to_string()))
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+//! Applying migrations, with a guard against destructive ones.
+//!
+//! A migration is destructive when a line of its SQL is exactly
+//! `-- destructive`, or when its version is in `DESTRUCTIVE_VERSIONS` (for
+//! migrations shipped before the marker existed; editing them would change
+//! their checksum). Destructive migrations are only applied to a database
+//! without data, unless explicitly allowed.
+
+use anyhow::bail;
+use sqlx::{migrate::Migration, PgPool};
+use std::collections::HashMap;
+
+use super::pool::MIGRATOR;
+
+const DESTRUCTIVE_MARKER: &str = "-- destructive";
+
+const DESTRUCTIVE_VERSIONS: &[i64] = &[
+    20240101000000, // reset_database, drops and recreates every table
+];
+
+/// Migrations not applied yet, in order
+pub async fn pending(pool: &PgPool) -> anyhow::Result<Vec<&'static Migration>> {
+    let applied = applied_migrations(pool).await?;
+
+    let mut pending = Vec::new();
+    for migration in MIGRATOR.iter() {
+        if migration.migration_type.is_down_migration() {
+            continue;
+        }
+
+        match applied.get(&migration.version) {
+            Some(checksum) if checksum.as_slice() != &*migration.checksum => bail!(
+                "Migration {} ({}) was modified after it was applied",
+                migration.version,
+                migration.description
+            ),
+            Some(_) => {}
+            None => pending.push(migration),
+        }
+    }
+
+    Ok(pending)
+}
+
+pub fn is_destructive(migration: &Migration) -> bool {
+    DESTRUCTIVE_VERSIONS.contains(&migration.version)
+        || migration
+            .sql
+            .lines()
+            .any(|line| line.trim() == DESTRUCTIVE_MARKER)
+}
+
+/// Apply pending migrations, returning how many were applied. Fails without
+/// applying anything if one is destructive and the database has data, unless
+/// `allow_destructive` is set.
+pub async fn run(pool: &PgPool, allow_destructive: bool) -> anyhow::Result<usize> {
+    let pending = pending(pool).await?;
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    let destructive: Vec<String> = pending
+        .iter()
+        .filter(|migration| is_destructive(migration))
+        .map(|migration| format!("{} ({})", migration.version, migration.description))
+        .collect();
+
+    if !destructive.is_empty() {
+        if allow_destructive {
+            tracing::warn!(
+                "Applying destructive migrations: {}",
+                destructive.join(", ")
+            );
+        } else if has_data(pool).await? {
+            bail!(
+                "Refusing to apply destructive migrations to a database that has data: {}. \
+                 Back it up, then pass --allow-destructive-migrations to apply them anyway",
+                destructive.join(", ")
+            );
+        }
+    }
+
+    MIGRATOR.run(pool).await?;
+
+    Ok(pending.len())
+}
+
+/// Checksums of applied migrations by version
+async fn applied_migrations(pool: &PgPool) -> anyhow::Result<HashMap<i64, Vec<u8>>> {
+    // A dry run must not create sqlx's bookkeeping table, so check for it first
+    let exists: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+        .fetch_one(pool)
+        .await?;
+
+    if !exists {
+        return Ok(HashMap::new());
+    }
+
+    let rows: Vec<(i64, Vec<u8>)> =
+        sqlx::query_as("SELECT version, checksum FROM _sqlx_migrations WHERE success")
+            .fetch_all(pool)
+            .await?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// Whether any table in the current schema, other than sqlx's own, has a row
+async fn has_data(pool: &PgPool) -> anyhow::Result<bool> {
+    let tables: Vec<String> = sqlx::query_scalar(
+        "SELECT quote_ident(tablename) FROM pg_tables
+         WHERE schemaname = current_schema() AND tablename <> '_sqlx_migrations'",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for table in tables {
+        let query = format!("SELECT EXISTS (SELECT 1 FROM {})", table);
+        if sqlx::query_scalar::<_, bool>(&query)
+            .fetch_one(pool)
+            .await?
+        {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::migrate::MigrationType;
+
+    fn migration(version: i64, sql: &'static str) -> Migration {
+        Migration::new(version, "test".into(), MigrationType::Simple, sql.into())
+    }
+
+    #[test]
+    fn marker_line_makes_a_migration_destructive() {
+        assert!(is_destructive(&migration(
+            20990101000000,
+            "-- Drop the old table\n-- destructive\nDROP TABLE old;"
+        )));
+        assert!(is_destructive(&migration(
+            20990101000000,
+            "DROP TABLE old;\n   -- destructive   \n"
+        )));
+    }
+
+    #[test]
+    fn listed_versions_are_destructive() {
+        assert!(is_destructive(&migration(20240101000000, "SELECT 1;")));
+    }
+
+    #[test]
+    fn other_migrations_are_not_destructive() {
+        assert!(!is_destructive(&migration(
+            20990101000000,
+            "ALTER TABLE novels ADD COLUMN IF NOT EXISTS subtitle TEXT;"
+        )));
+        // The marker only counts as a line of its own
+        assert!(!is_destructive(&migration(
+            20990101000000,
+            "-- not destructive, despite the name\nSELECT '-- destructive';"
+        )));
+    }
+
+    #[test]
+    fn listed_versions_exist() {
+        // A mistyped version would silently disable the guard for its migration
+        for version in DESTRUCTIVE_VERSIONS {
+            let migration = MIGRATOR
+                .iter()
+                .find(|migration| migration.version == *version)
+                .unwrap_or_else(|| panic!("no migration {}", version));
+            assert!(is_destructive(migration));
+        }
+    }
+}
@@ -1,10 +1,45 @@
-use crate::config::Config;
+use crate::{
+    auth::{keys::JwtKeys, oidc::OidcClient, revocation::RevocationList, throttle::LoginThrottle},
+    config::Config,
+    metrics::Metrics,
+};
 use sqlx::PgPool;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: PgPool,
     pub config: Config,
+    pub jwt_keys: Arc<JwtKeys>,
+    pub revocations: RevocationList,
+    pub login_throttle: LoginThrottle,
+    pub oidc: OidcClient,
+    pub metrics: Arc<Metrics>,
 }
 
+#[cfg(test)]
+impl AppState {
+    /// State with the default config and a pool whose queries fail at once,
+    /// for tests that call routers without a database
+    pub fn for_tests() -> Self {
+        let config = Config::default();
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_millis(100))
+            .connect_lazy("postgres://127.0.0.1:1/unused")
+            .unwrap();
+
+        AppState {
+            jwt_keys: Arc::new(JwtKeys::from_config(&config).unwrap()),
+            revocations: RevocationList::new(pool.clone()),
+            login_throttle: LoginThrottle::new(std::time::Duration::from_secs(60)),
+            oidc: OidcClient::new(&config).unwrap(),
+            metrics: Arc::new(Metrics::new(config.database.max_connections).unwrap()),
+            pool,
+            config,
+        }
+    }
+}
+
+pub mod audit;
+pub mod migrations;
 pub mod pool;
@@ -1,4 +1,66 @@
-use sqlx::PgPool;
+use sqlx::{
+    migrate::Migrator,
+    postgres::{PgConnectOptions, PgPoolOptions},
+    ConnectOptions, Connection, PgPool,
+};
+use std::time::{Duration, Instant};
+
+use crate::config::DatabaseConfig;
+
+/// Migrations embedded in the binary at build time
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Longest pause between connection attempts
+const MAX_CONNECT_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Open a connection pool to the database. Postgres may still be starting
+/// (fly cold starts both), so failures that can clear up on their own are
+/// retried with backoff for up to `connect_timeout` seconds.
+pub async fn connect(config: &DatabaseConfig) -> Result<PgPool, sqlx::Error> {
+    // Slow statements are logged at WARN inside the request span, so the log
+    // line carries the route and request id that issued them
+    let options = config.url.parse::<PgConnectOptions>()?.log_slow_statements(
+        log::LevelFilter::Warn,
+        Duration::from_millis(config.slow_statement_ms),
+    );
+
+    let deadline = Instant::now() + Duration::from_secs(config.connect_timeout);
+    let mut backoff = Duration::from_millis(250);
+
+    // Probe with single connections; the pool would keep retrying on its own
+    // for its whole acquire timeout, hiding the cause
+    loop {
+        match options.connect().await {
+            Ok(connection) => {
+                let _ = connection.close().await;
+                break;
+            }
+            Err(e) if is_transient(&e) && Instant::now() < deadline => {
+                let wait = backoff.min(deadline.saturating_duration_since(Instant::now()));
+                tracing::warn!("Database not reachable yet, retrying in {:?}: {}", wait, e);
+                tokio::time::sleep(wait).await;
+                backoff = (backoff * 2).min(MAX_CONNECT_BACKOFF);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    PgPoolOptions::new()
+        .max_connections(config.max_connections)
+        .connect_with(options)
+        .await
+}
+
+/// Whether a connection error can go away by waiting; bad credentials or a
+/// missing database can't
+fn is_transient(e: &sqlx::Error) -> bool {
+    match e {
+        sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut => true,
+        // cannot_connect_now (starting up or shutting down), too_many_connections
+        sqlx::Error::Database(e) => matches!(e.code().as_deref(), Some("57P03" | "53300")),
+        _ => false,
+    }
+}
 
 /// Test database connection
 pub async fn test_connection(pool: &PgPool) -> Result<(), sqlx::Error> {
@@ -13,3 +75,10 @@ pub async fn get_db_version(pool: &PgPool) -> Result<String, sqlx::Error> {
 
     Ok(row.0)
 }
+
+/// Latest successfully applied migration, if any
+pub async fn schema_version(pool: &PgPool) -> Result<Option<i64>, sqlx::Error> {
+    sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success")
+        .fetch_one(pool)
+        .await
+}
@@ -0,0 +1,182 @@
+//! Catalog of the error codes sent as `code` in problem responses.
+//!
+//! This is the single list of codes clients can rely on. Codes are stable:
+//! once shipped, a code is never renamed or reused for a different error.
+//! The `detail` shown for each code comes from the `[error]` table of the
+//! message catalogs in `locales/`.
+
+use utoipa::openapi::{
+    schema::{ObjectBuilder, Schema, Type},
+    RefOr,
+};
+
+use crate::i18n::{self, Locale};
+
+macro_rules! error_codes {
+    ($($(#[$doc:meta])* $variant:ident => $code:literal;)*) => {
+        /// Stable, machine-readable error code
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ErrorCode {
+            $($(#[$doc])* $variant,)*
+        }
+
+        impl ErrorCode {
+            #[cfg(test)]
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant,)*];
+
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => $code,)*
+                }
+            }
+        }
+
+        impl utoipa::PartialSchema for ErrorCode {
+            fn schema() -> RefOr<Schema> {
+                ObjectBuilder::new()
+                    .schema_type(Type::String)
+                    .enum_values(Some([$($code,)*]))
+                    .into()
+            }
+        }
+
+        impl utoipa::ToSchema for ErrorCode {}
+    };
+}
+
+error_codes! {
+    // Generic
+    /// 500, details are only logged
+    InternalError => "internal_error";
+    /// 400, see the `errors` map for the failing fields
+    ValidationFailed => "validation_failed";
+    /// 400
+    NoFieldsToUpdate => "request.no_fields";
+    /// 400, the body is not JSON or does not match the expected shape
+    InvalidBody => "request.invalid_body";
+    /// 400, the body must be sent as `application/json`
+    JsonContentTypeRequired => "request.json_content_type";
+    /// 413
+    BodyTooLarge => "request.body_too_large";
+    /// 400, a path segment is malformed, e.g. an id that is not a UUID
+    InvalidPathParameter => "request.invalid_path";
+    /// 400
+    InvalidQuery => "request.invalid_query";
+    /// 429, see the `Retry-After` header
+    RateLimited => "rate_limited";
+    /// 404, no route matches the path
+    RouteNotFound => "route.not_found";
+    /// 405, see the `Allow` header
+    MethodNotAllowed => "route.method_not_allowed";
+    /// 404, fallback when no more specific code applies
+    ResourceNotFound => "resource.not_found";
+    /// 409, fallback when no more specific code applies
+    ResourceConflict => "resource.conflict";
+    /// 409, a referenced record does not exist
+    InvalidReference => "resource.invalid_reference";
+    /// 400, fallback for check constraints
+    ConstraintViolation => "resource.constraint_violation";
+
+    // Authentication and authorization
+    /// 401
+    Unauthorized => "auth.unauthorized";
+    /// 401
+    InvalidToken => "auth.invalid_token";
+    /// 401, refresh the access token and retry
+    TokenExpired => "auth.token_expired";
+    /// 401
+    InvalidCredentials => "auth.invalid_credentials";
+    /// 403
+    AccountDisabled => "auth.account_disabled";
+    /// 403, the `scope` member names the missing scope
+    MissingScope => "auth.missing_scope";
+    /// 403
+    InsufficientRole => "auth.insufficient_role";
+    /// 403
+    SessionRequired => "auth.session_required";
+    /// 403
+    RegistrationDisabled => "auth.registration_disabled";
+    /// 409
+    AlreadyRegistered => "auth.already_registered";
+    /// 400
+    PasswordUnchanged => "auth.password_unchanged";
+    /// 400
+    RevokeInFuture => "auth.revoke_in_future";
+
+    // Two-factor authentication
+    /// 409
+    TotpAlreadyEnabled => "totp.already_enabled";
+    /// 400
+    TotpSetupNotStarted => "totp.setup_not_started";
+    /// 400
+    TotpNotEnabled => "totp.not_enabled";
+    /// 400
+    TotpCodeRequired => "totp.code_required";
+
+    // OpenID Connect
+    /// 404
+    OidcProviderNotFound => "oidc.provider_not_found";
+    /// 403
+    IdentityNotLinked => "oidc.identity_not_linked";
+    /// 409
+    IdentityAlreadyLinked => "identity.already_linked";
+    /// 404
+    IdentityNotFound => "identity.not_found";
+
+    // Admins
+    /// 404
+    AdminNotFound => "admin.not_found";
+    /// 409
+    UsernameTaken => "admin.username_taken";
+    /// 400
+    InvalidRole => "admin.invalid_role";
+    /// 400
+    CannotChangeOwnRole => "admin.own_role";
+    /// 400
+    CannotDisableSelf => "admin.disable_self";
+
+    // API tokens
+    /// 404
+    ApiTokenNotFound => "api_token.not_found";
+    /// 400
+    ApiTokenExpiryInPast => "api_token.expiry_in_past";
+
+    // Content
+    /// 404
+    NovelNotFound => "novel.not_found";
+    /// 409
+    NovelSlugTaken => "novel.duplicate_slug";
+    /// 409
+    RelationDuplicate => "novel_relation.duplicate";
+    /// 404
+    ChapterNotFound => "chapter.not_found";
+    /// 409
+    ChapterDuplicateNumber => "chapter.duplicate_number";
+    /// 404
+    BlogPostNotFound => "blog_post.not_found";
+    /// 409
+    BlogPostSlugTaken => "blog_post.duplicate_slug";
+    /// 404
+    AppNotFound => "app.not_found";
+    /// 409
+    AppSlugTaken => "app.duplicate_slug";
+}
+
+impl ErrorCode {
+    /// Human readable message for the code
+    pub fn message(self, locale: Locale) -> &'static str {
+        i18n::text(locale, &format!("error.{}", self.as_str()))
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl serde::Serialize for ErrorCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
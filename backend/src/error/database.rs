@@ -0,0 +1,143 @@
+//! Mapping of Postgres constraint violations to domain errors.
+//!
+//! Every constraint listed here is named explicitly by a migration (see
+//! `20250210000000_name_constraints.sql`), so the mapping does not depend on
+//! the names Postgres generates.
+
+use sqlx::error::ErrorKind;
+
+use super::{AppError, ErrorCode};
+
+/// Constraint name, and the error and code a violation becomes
+type ConstraintError = (&'static str, fn(ErrorCode) -> AppError, ErrorCode);
+
+/// Domain error for each constraint clients can trip
+#[rustfmt::skip]
+const CONSTRAINTS: &[ConstraintError] = &[
+    // Unique
+    ("novels_slug_unique",               AppError::Conflict,   ErrorCode::NovelSlugTaken),
+    ("novel_chapters_number_unique",     AppError::Conflict,   ErrorCode::ChapterDuplicateNumber),
+    ("unique_novel_relations",           AppError::Conflict,   ErrorCode::RelationDuplicate),
+    ("blog_posts_slug_unique",           AppError::Conflict,   ErrorCode::BlogPostSlugTaken),
+    ("apps_slug_unique",                 AppError::Conflict,   ErrorCode::AppSlugTaken),
+    ("admins_username_unique",           AppError::Conflict,   ErrorCode::UsernameTaken),
+    ("admin_identities_subject_unique",  AppError::Conflict,   ErrorCode::IdentityAlreadyLinked),
+    // Foreign keys; the referenced row is gone, e.g. deleted concurrently
+    ("novel_chapters_novel_fk",          AppError::NotFound,   ErrorCode::NovelNotFound),
+    ("novel_relations_novel_fk",         AppError::NotFound,   ErrorCode::NovelNotFound),
+    ("novel_relations_related_novel_fk", AppError::NotFound,   ErrorCode::NovelNotFound),
+    ("app_distributions_app_fk",         AppError::NotFound,   ErrorCode::AppNotFound),
+    ("api_tokens_admin_fk",              AppError::NotFound,   ErrorCode::AdminNotFound),
+    ("admin_identities_admin_fk",        AppError::NotFound,   ErrorCode::AdminNotFound),
+    // Checks
+    ("admins_role_check",                AppError::BadRequest, ErrorCode::InvalidRole),
+    ("admin_invitations_role_valid",     AppError::BadRequest, ErrorCode::InvalidRole),
+];
+
+fn constraint_error(constraint: &str) -> Option<AppError> {
+    CONSTRAINTS
+        .iter()
+        .find(|(name, _, _)| *name == constraint)
+        .map(|(_, error, code)| error(*code))
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        let sqlx::Error::Database(db_err) = &err else {
+            return match err {
+                sqlx::Error::RowNotFound => AppError::NotFound(ErrorCode::ResourceNotFound),
+                _ => AppError::DatabaseError(err),
+            };
+        };
+
+        let mapped = db_err.constraint().and_then(constraint_error);
+
+        let error = match (mapped, db_err.kind()) {
+            (Some(error), _) => error,
+            (None, ErrorKind::UniqueViolation) => AppError::Conflict(ErrorCode::ResourceConflict),
+            (None, ErrorKind::ForeignKeyViolation) => {
+                AppError::Conflict(ErrorCode::InvalidReference)
+            }
+            (None, ErrorKind::CheckViolation) => {
+                AppError::BadRequest(ErrorCode::ConstraintViolation)
+            }
+            _ => return AppError::DatabaseError(err),
+        };
+
+        tracing::debug!(
+            constraint = db_err.constraint(),
+            "Constraint violation mapped to {}: {}",
+            error.code(),
+            db_err
+        );
+
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use sqlx::PgPool;
+
+    use crate::db::pool::MIGRATOR;
+
+    #[test]
+    fn maps_constraints_to_codes() {
+        let mapped = |name| constraint_error(name).map(|error| (error.status(), error.code()));
+
+        assert_eq!(
+            mapped("novels_slug_unique"),
+            Some((StatusCode::CONFLICT, ErrorCode::NovelSlugTaken))
+        );
+        assert_eq!(
+            mapped("novel_relations_related_novel_fk"),
+            Some((StatusCode::NOT_FOUND, ErrorCode::NovelNotFound))
+        );
+        assert_eq!(
+            mapped("admin_invitations_role_valid"),
+            Some((StatusCode::BAD_REQUEST, ErrorCode::InvalidRole))
+        );
+        assert_eq!(mapped("novels_slug_key"), None);
+        assert_eq!(mapped("refresh_tokens_token_hash_key"), None);
+    }
+
+    #[test]
+    fn every_constraint_is_named_by_a_migration() {
+        for (name, _, _) in CONSTRAINTS {
+            assert!(
+                MIGRATOR
+                    .iter()
+                    .any(|migration| migration.sql.contains(&format!("'{}'", name))
+                        || migration.sql.contains(&format!("CONSTRAINT {} ", name))),
+                "no migration names {}",
+                name
+            );
+        }
+    }
+
+    /// The constraints exist under these names once every migration has run
+    #[tokio::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn constraints_exist_in_a_migrated_database() {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL is not set");
+        let pool = PgPool::connect(&url).await.unwrap();
+        crate::db::migrations::run(&pool, false).await.unwrap();
+
+        let existing: Vec<String> = sqlx::query_scalar(
+            "SELECT conname::text FROM pg_constraint WHERE connamespace = current_schema()::regnamespace",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+
+        for (name, _, _) in CONSTRAINTS {
+            assert!(
+                existing.iter().any(|existing| existing == name),
+                "{} is missing",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,376 @@
+use axum::{
+    extract::rejection::{JsonRejection, PathRejection, QueryRejection},
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::{collections::BTreeMap, fmt};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::{ValidationErrors, ValidationErrorsKind};
+
+use crate::{
+    i18n::{self, Locale},
+    models::Scope,
+};
+
+mod code;
+mod database;
+
+pub use code::ErrorCode;
+
+/// Media type of error responses (RFC 7807)
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Application error type
+#[derive(Debug)]
+pub enum AppError {
+    // Database errors
+    DatabaseError(sqlx::Error),
+
+    // Authentication errors
+    Unauthorized,
+    InvalidToken,
+    TokenExpired,
+    InvalidCredentials,
+
+    // Authorization errors
+    Forbidden(ErrorCode),
+    MissingScope(Scope),
+
+    // Validation errors
+    ValidationError(ValidationErrors),
+
+    // Not found errors
+    NotFound(ErrorCode),
+
+    // Conflict errors
+    Conflict(ErrorCode),
+
+    // Internal errors
+    InternalError(String),
+
+    // Bad request
+    BadRequest(ErrorCode),
+
+    // Request body over the configured limit
+    PayloadTooLarge,
+
+    // Rate limiting; retry_after is in seconds
+    TooManyRequests { retry_after: u64 },
+}
+
+impl AppError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            AppError::DatabaseError(_) | AppError::InternalError(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            AppError::Unauthorized
+            | AppError::InvalidToken
+            | AppError::TokenExpired
+            | AppError::InvalidCredentials => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) | AppError::MissingScope(_) => StatusCode::FORBIDDEN,
+            AppError::ValidationError(_) | AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::DatabaseError(_) | AppError::InternalError(_) => ErrorCode::InternalError,
+            AppError::Unauthorized => ErrorCode::Unauthorized,
+            AppError::InvalidToken => ErrorCode::InvalidToken,
+            AppError::TokenExpired => ErrorCode::TokenExpired,
+            AppError::InvalidCredentials => ErrorCode::InvalidCredentials,
+            AppError::MissingScope(_) => ErrorCode::MissingScope,
+            AppError::ValidationError(_) => ErrorCode::ValidationFailed,
+            AppError::TooManyRequests { .. } => ErrorCode::RateLimited,
+            AppError::PayloadTooLarge => ErrorCode::BodyTooLarge,
+            AppError::Forbidden(code)
+            | AppError::NotFound(code)
+            | AppError::Conflict(code)
+            | AppError::BadRequest(code) => *code,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::DatabaseError(e) => write!(f, "Database error: {}", e),
+            AppError::InternalError(msg) => write!(f, "Internal error: {}", msg),
+            AppError::MissingScope(scope) => write!(f, "Token is missing the {} scope", scope),
+            AppError::ValidationError(errors) => write!(f, "Validation error: {}", errors),
+            AppError::TooManyRequests { retry_after } => {
+                write!(f, "Too many requests, retry after {}s", retry_after)
+            }
+            _ => f.write_str(self.code().message(Locale::En)),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+/// Problem details body (RFC 7807)
+///
+/// Kept in the response extensions so middleware can add the request id.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(description = "Problem details body (RFC 7807)")]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub title: &'static str,
+    pub status: u16,
+    pub detail: String,
+    pub code: ErrorCode,
+    /// Failing fields, keyed by path (`title`, `chapters[0].content`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<BTreeMap<String, Vec<FieldError>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// Set on internal errors to find the logged cause
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl Problem {
+    pub fn new(status: StatusCode, code: ErrorCode) -> Self {
+        Problem {
+            kind: "about:blank",
+            title: status.canonical_reason().unwrap_or("Error"),
+            status: status.as_u16(),
+            detail: code.message(Locale::default()).to_string(),
+            code,
+            errors: None,
+            scope: None,
+            correlation_id: None,
+            request_id: None,
+        }
+    }
+}
+
+impl Problem {
+    /// Translate the detail and field messages
+    pub fn localize(&mut self, locale: Locale) {
+        self.detail = self.code.message(locale).to_string();
+
+        for errors in self
+            .errors
+            .iter_mut()
+            .flat_map(|fields| fields.values_mut())
+        {
+            for error in errors.iter_mut().filter(|error| error.default_message) {
+                error.message = field_message(locale, &error.code, &error.params);
+            }
+        }
+    }
+}
+
+impl IntoResponse for Problem {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = (status, Json(&self)).into_response();
+
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(PROBLEM_CONTENT_TYPE),
+        );
+        response.extensions_mut().insert(self);
+
+        response
+    }
+}
+
+/// A single failed validation rule
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FieldError {
+    /// Validator name, e.g. `length`
+    pub code: String,
+    pub message: String,
+    /// Rule arguments such as `min` and `max`
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    #[schema(value_type = Object)]
+    pub params: Map<String, Value>,
+    /// Whether the message comes from the catalog rather than the validator
+    #[serde(skip)]
+    pub default_message: bool,
+}
+
+impl From<&validator::ValidationError> for FieldError {
+    fn from(error: &validator::ValidationError) -> Self {
+        // The rejected value is left out so passwords are never echoed back
+        let params: Map<String, Value> = error
+            .params
+            .iter()
+            .filter(|(name, _)| *name != "value")
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+
+        let (message, default_message) = match &error.message {
+            Some(message) => (message.to_string(), false),
+            None => (field_message(Locale::default(), &error.code, &params), true),
+        };
+
+        FieldError {
+            code: error.code.to_string(),
+            message,
+            params,
+            default_message,
+        }
+    }
+}
+
+fn field_message(locale: Locale, code: &str, params: &Map<String, Value>) -> String {
+    let param = |name: &str| params.get(name).and_then(Value::as_u64);
+
+    let key = match code {
+        "length" => match (param("equal"), param("min"), param("max")) {
+            (Some(_), _, _) => "validation.length_equal",
+            (None, Some(1), None) => "validation.required",
+            (None, Some(min), Some(_)) if min > 0 => "validation.length_between",
+            (None, _, Some(_)) => "validation.length_max",
+            (None, Some(_), None) => "validation.length_min",
+            (None, None, None) => "validation.invalid",
+        },
+        _ => "validation.invalid",
+    };
+
+    i18n::format(locale, key, params)
+}
+
+/// Flatten nested validation errors into a map keyed by field path
+fn field_errors(errors: &ValidationErrors) -> BTreeMap<String, Vec<FieldError>> {
+    let mut fields = BTreeMap::new();
+    collect_field_errors(errors, None, &mut fields);
+    fields
+}
+
+fn collect_field_errors(
+    errors: &ValidationErrors,
+    prefix: Option<&str>,
+    fields: &mut BTreeMap<String, Vec<FieldError>>,
+) {
+    for (field, kind) in errors.errors() {
+        let path = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, field),
+            None => field.to_string(),
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(errors) => fields
+                .entry(path)
+                .or_default()
+                .extend(errors.iter().map(FieldError::from)),
+            ValidationErrorsKind::Struct(nested) => {
+                collect_field_errors(nested, Some(&path), fields)
+            }
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect_field_errors(nested, Some(&format!("{}[{}]", path, index)), fields);
+                }
+            }
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let mut problem = Problem::new(self.status(), self.code());
+
+        match self {
+            // Only the correlation id reaches the client; the cause stays in the logs
+            AppError::DatabaseError(e) => {
+                let correlation_id = Uuid::new_v4();
+                tracing::error!(%correlation_id, "Database error: {:?}", e);
+                problem.correlation_id = Some(correlation_id);
+            }
+            AppError::InternalError(msg) => {
+                let correlation_id = Uuid::new_v4();
+                tracing::error!(%correlation_id, "Internal error: {}", msg);
+                problem.correlation_id = Some(correlation_id);
+            }
+            AppError::MissingScope(scope) => {
+                problem.scope = Some(scope.to_string());
+            }
+            AppError::ValidationError(errors) => {
+                problem.errors = Some(field_errors(&errors));
+            }
+            AppError::TooManyRequests { retry_after } => {
+                let mut response = problem.into_response();
+                response
+                    .headers_mut()
+                    .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+                return response;
+            }
+            _ => {}
+        }
+
+        problem.into_response()
+    }
+}
+
+// Implement From traits for common error types
+impl From<jsonwebtoken::errors::Error> for AppError {
+    fn from(err: jsonwebtoken::errors::Error) -> Self {
+        match err.kind() {
+            jsonwebtoken::errors::ErrorKind::ExpiredSignature => AppError::TokenExpired,
+            _ => AppError::InvalidToken,
+        }
+    }
+}
+
+impl From<argon2::password_hash::Error> for AppError {
+    fn from(_: argon2::password_hash::Error) -> Self {
+        AppError::InvalidCredentials
+    }
+}
+
+impl From<validator::ValidationErrors> for AppError {
+    fn from(err: validator::ValidationErrors) -> Self {
+        AppError::ValidationError(err)
+    }
+}
+
+// Extractor rejections, so malformed requests get problem responses too
+impl From<JsonRejection> for AppError {
+    fn from(rejection: JsonRejection) -> Self {
+        tracing::debug!("Rejected request body: {}", rejection.body_text());
+
+        match rejection {
+            JsonRejection::MissingJsonContentType(_) => {
+                AppError::BadRequest(ErrorCode::JsonContentTypeRequired)
+            }
+            rejection if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE => {
+                AppError::PayloadTooLarge
+            }
+            _ => AppError::BadRequest(ErrorCode::InvalidBody),
+        }
+    }
+}
+
+impl From<PathRejection> for AppError {
+    fn from(rejection: PathRejection) -> Self {
+        // Server errors mean the route and its extractor disagree, not a bad request
+        if rejection.status().is_server_error() {
+            return AppError::InternalError(rejection.body_text());
+        }
+
+        tracing::debug!("Rejected path: {}", rejection.body_text());
+        AppError::BadRequest(ErrorCode::InvalidPathParameter)
+    }
+}
+
+impl From<QueryRejection> for AppError {
+    fn from(rejection: QueryRejection) -> Self {
+        tracing::debug!("Rejected query: {}", rejection.body_text());
+        AppError::BadRequest(ErrorCode::InvalidQuery)
+    }
+}
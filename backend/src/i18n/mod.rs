@@ -0,0 +1,230 @@
+//! Message catalogs for localized API text.
+//!
+//! Catalogs live in `locales/<lang>.toml` and are compiled into the binary.
+//! Nested tables flatten to dotted keys, so `[novel_type.short] name` is
+//! `novel_type.short.name`. Missing keys fall back to Korean.
+
+use serde_json::{Map, Value};
+use std::{collections::HashMap, fmt, sync::OnceLock};
+
+type Catalog = HashMap<String, String>;
+
+/// Supported response language
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    Ko,
+    En,
+}
+
+impl Locale {
+    pub const ALL: &'static [Locale] = &[Locale::Ko, Locale::En];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Locale::Ko => "ko",
+            Locale::En => "en",
+        }
+    }
+
+    /// Match a language tag such as `en-US` by its primary subtag
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?;
+
+        if primary.eq_ignore_ascii_case("ko") {
+            Some(Locale::Ko)
+        } else if primary.eq_ignore_ascii_case("en") {
+            Some(Locale::En)
+        } else {
+            None
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Locale::Ko => include_str!("../../locales/ko.toml"),
+            Locale::En => include_str!("../../locales/en.toml"),
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+fn catalog(locale: Locale) -> &'static Catalog {
+    static KO: OnceLock<Catalog> = OnceLock::new();
+    static EN: OnceLock<Catalog> = OnceLock::new();
+
+    let cell = match locale {
+        Locale::Ko => &KO,
+        Locale::En => &EN,
+    };
+
+    cell.get_or_init(|| {
+        parse(locale.source())
+            .unwrap_or_else(|e| panic!("Invalid {} message catalog: {}", locale, e))
+    })
+}
+
+fn parse(source: &str) -> Result<Catalog, toml::de::Error> {
+    let table: toml::Table = toml::from_str(source)?;
+    let mut catalog = Catalog::new();
+    flatten(&table, None, &mut catalog);
+    Ok(catalog)
+}
+
+fn flatten(table: &toml::Table, prefix: Option<&str>, catalog: &mut Catalog) {
+    for (key, value) in table {
+        let key = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, key),
+            None => key.clone(),
+        };
+
+        match value {
+            toml::Value::Table(nested) => flatten(nested, Some(&key), catalog),
+            toml::Value::String(text) => {
+                catalog.insert(key, text.clone());
+            }
+            other => {
+                catalog.insert(key, other.to_string());
+            }
+        }
+    }
+}
+
+/// Text for `key`, falling back to Korean
+pub fn text(locale: Locale, key: &str) -> &'static str {
+    if let Some(text) = catalog(locale).get(key) {
+        return text;
+    }
+
+    match catalog(Locale::default()).get(key) {
+        Some(text) => text,
+        None => {
+            tracing::warn!("Missing message catalog key {}", key);
+            ""
+        }
+    }
+}
+
+/// Text for `key` with each `{name}` replaced by the matching param
+pub fn format(locale: Locale, key: &str, params: &Map<String, Value>) -> String {
+    params
+        .iter()
+        .fold(text(locale, key).to_string(), |text, (name, value)| {
+            let value = match value {
+                Value::String(value) => value.clone(),
+                other => other.to_string(),
+            };
+            text.replace(&format!("{{{}}}", name), &value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::ErrorCode,
+        models::{
+            app::{DISTRIBUTION_CHANNELS, PLATFORMS},
+            novel::{GENRES, NOVEL_TYPES},
+        },
+    };
+    use std::collections::BTreeSet;
+
+    fn keys(locale: Locale) -> BTreeSet<&'static str> {
+        catalog(locale).keys().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn every_key_exists_in_every_locale() {
+        let all: BTreeSet<&str> = Locale::ALL.iter().flat_map(|l| keys(*l)).collect();
+
+        for locale in Locale::ALL {
+            let keys = keys(*locale);
+            let missing: Vec<_> = all.difference(&keys).collect();
+            assert!(
+                missing.is_empty(),
+                "{} catalog is missing {:?}",
+                locale,
+                missing
+            );
+        }
+    }
+
+    #[test]
+    fn catalogs_have_no_empty_messages() {
+        for locale in Locale::ALL {
+            for (key, text) in catalog(*locale) {
+                assert!(!text.trim().is_empty(), "{} {} is empty", locale, key);
+            }
+        }
+    }
+
+    #[test]
+    fn every_lookup_key_is_in_the_catalog() {
+        let mut expected: Vec<String> = Vec::new();
+        expected.extend(ErrorCode::ALL.iter().map(|code| format!("error.{}", code)));
+        expected.extend(GENRES.iter().map(|id| format!("genre.{}", id)));
+        for id in NOVEL_TYPES {
+            expected.push(format!("novel_type.{}.name", id));
+            expected.push(format!("novel_type.{}.description", id));
+        }
+        expected.extend(PLATFORMS.iter().map(|id| format!("platform.{}", id)));
+        expected.extend(
+            DISTRIBUTION_CHANNELS
+                .iter()
+                .map(|id| format!("channel.{}", id)),
+        );
+        expected.extend(
+            [
+                "length_equal",
+                "length_between",
+                "length_min",
+                "length_max",
+                "required",
+                "invalid",
+            ]
+            .iter()
+            .map(|key| format!("validation.{}", key)),
+        );
+
+        for locale in Locale::ALL {
+            for key in &expected {
+                assert!(
+                    catalog(*locale).contains_key(key),
+                    "{} catalog is missing {}",
+                    locale,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn formats_templates() {
+        let mut params = Map::new();
+        params.insert("min".to_string(), Value::from(3));
+        params.insert("max".to_string(), Value::from(100));
+
+        assert_eq!(
+            format(Locale::En, "validation.length_between", &params),
+            "Must be between 3 and 100 characters"
+        );
+        assert_eq!(
+            format(Locale::Ko, "validation.length_between", &params),
+            "3자 이상 100자 이하로 입력하세요"
+        );
+    }
+
+    #[test]
+    fn parses_language_tags() {
+        assert_eq!(Locale::from_tag("en-US"), Some(Locale::En));
+        assert_eq!(Locale::from_tag("KO_kr"), Some(Locale::Ko));
+        assert_eq!(Locale::from_tag("ja"), None);
+        assert_eq!(Locale::from_tag("*"), None);
+    }
+}
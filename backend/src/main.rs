@@ -1,112 +1,267 @@
-use axum::{
-    http::{header, Method, StatusCode},
-    response::IntoResponse,
-    routing::get,
-    Json, Router,
-};
+use axum::{extract::DefaultBodyLimit, response::IntoResponse, routing::get, Json, Router};
+use clap::Parser;
 use serde_json::json;
-use sqlx::postgres::PgPoolOptions;
-use std::env;
-use tower_http::{
-    cors::CorsLayer,
-    trace::{DefaultMakeSpan, TraceLayer},
-};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use std::{future::IntoFuture, net::SocketAddr, sync::Arc, time::Duration};
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+use tower_http::trace::TraceLayer;
+use utoipa_swagger_ui::SwaggerUi;
 
+mod auth;
+mod cli;
 mod config;
 mod db;
 mod error;
+mod i18n;
+mod metrics;
 mod middleware;
 mod models;
+mod openapi;
 mod routes;
+mod telemetry;
 
+use auth::{keys::JwtKeys, oidc::OidcClient, revocation::RevocationList, throttle::LoginThrottle};
+use cli::{Cli, Command, ServeArgs};
 use config::Config;
-use error::AppError;
+use error::{AppError, ErrorCode};
+use middleware::api_version::{self, ApiVersion};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
     // Load environment variables
     dotenvy::dotenv().ok();
 
-    // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "backend=debug,tower_http=debug,axum=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
-
     // Load configuration
-    let config = Config::from_env()?;
+    let config = Config::load(cli.config.as_deref())?;
+
+    // Initialize logging and trace export
+    let _telemetry = telemetry::init(&config)?;
+
+    tracing::info!("Loaded {} configuration", config.profile);
+
+    match cli.command.unwrap_or(Command::Serve(ServeArgs::default())) {
+        Command::Serve(args) => serve(config, args).await,
+        command => cli::run(command, &config).await,
+    }
+}
+
+async fn serve(config: Config, args: ServeArgs) -> anyhow::Result<()> {
+    let jwt_keys = Arc::new(JwtKeys::from_config(&config)?);
 
     // Create database connection pool
-    let pool = PgPoolOptions::new()
-        .max_connections(5)
-        .connect(&config.database_url)
-        .await?;
+    let pool = db::pool::connect(&config.database).await?;
 
     // Run migrations
-    tracing::info!("Running database migrations...");
-    sqlx::migrate!("./migrations").run(&pool).await?;
+    if args.skip_migrations {
+        tracing::info!("Skipping database migrations");
+    } else {
+        tracing::info!("Running database migrations...");
+        db::migrations::run(&pool, args.allow_destructive_migrations).await?;
 
-    tracing::info!("Database migrations completed successfully");
+        tracing::info!("Database migrations completed successfully");
+    }
 
     // Build application state
     let app_state = db::AppState {
         pool: pool.clone(),
         config: config.clone(),
+        jwt_keys,
+        revocations: RevocationList::new(pool.clone()),
+        login_throttle: LoginThrottle::new(Duration::from_secs(
+            config.login.lockout_seconds.max(0) as u64,
+        )),
+        oidc: OidcClient::new(&config)?,
+        metrics: Arc::new(metrics::Metrics::new(config.database.max_connections)?),
     };
 
+    // Cancelled on SIGTERM/SIGINT; servers stop accepting and background tasks
+    // stop after their current unit of work
+    let shutdown = CancellationToken::new();
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            shutdown_signal().await;
+            tracing::info!("Shutdown signal received, draining connections");
+            shutdown.cancel();
+        }
+    });
+    let tasks = TaskTracker::new();
+
+    // Periodically drop revocation entries for tokens that have expired anyway
+    let revocations = app_state.revocations.clone();
+    tasks.spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(3600));
+            loop {
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = interval.tick() => {}
+                }
+
+                match revocations.purge_expired().await {
+                    Ok(purged) if purged > 0 => {
+                        tracing::info!("Purged {} expired token revocations", purged)
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Failed to purge expired token revocations: {}", e),
+                }
+            }
+        }
+    });
+
+    // Serve metrics on their own listener, e.g. one only reachable privately
+    match &config.metrics.bind {
+        Some(bind) => {
+            let listener = tokio::net::TcpListener::bind(bind).await?;
+            let metrics_app = routes::metrics::router().with_state(app_state.clone());
+
+            tracing::info!("Metrics listening on {}", bind);
+            let shutdown = shutdown.clone();
+            tasks.spawn(async move {
+                if let Err(e) = axum::serve(listener, metrics_app)
+                    .with_graceful_shutdown(shutdown.cancelled_owned())
+                    .await
+                {
+                    tracing::error!("Metrics server failed: {}", e);
+                }
+            });
+        }
+        None if config.metrics.token.is_none() => {
+            tracing::info!("Metrics disabled; set metrics.token or metrics.bind to enable them")
+        }
+        None => {}
+    }
+
     // Build router
     let app = create_router(app_state);
 
     // Start server
-    let addr = format!("0.0.0.0:{}", config.port);
+    let addr = format!("0.0.0.0:{}", config.server.port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
 
     tracing::info!("Server listening on {}", addr);
 
-    axum::serve(listener, app).await?;
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown.clone().cancelled_owned());
+
+    // Requests still running at the deadline are dropped
+    let drain_timeout = Duration::from_secs(config.server.shutdown_timeout);
+    let deadline = async {
+        shutdown.cancelled().await;
+        tokio::time::sleep(drain_timeout).await;
+    };
+
+    tokio::select! {
+        result = server.into_future() => result?,
+        _ = deadline => tracing::warn!(
+            "Requests still running after {}s, shutting down anyway",
+            drain_timeout.as_secs()
+        ),
+    }
+
+    // Background tasks only finish their current unit of work
+    tasks.close();
+    if tokio::time::timeout(Duration::from_secs(5), tasks.wait())
+        .await
+        .is_err()
+    {
+        tracing::warn!("Background tasks did not stop in time");
+    }
+
+    pool.close().await;
+    tracing::info!("Shutdown complete");
 
     Ok(())
 }
 
+/// Resolves on SIGINT (Ctrl-C) or SIGTERM, which fly.io sends before stopping a machine
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl-C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
 fn create_router(state: db::AppState) -> Router {
-    // CORS configuration - specific origins only
-    let cors = CorsLayer::new()
-        .allow_origin([
-            "http://localhost:3000".parse().unwrap(),
-            "https://0010capacity.github.io".parse().unwrap(),
-        ])
-        .allow_methods([
-            Method::GET,
-            Method::POST,
-            Method::PUT,
-            Method::DELETE,
-            Method::OPTIONS,
-        ])
-        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION]);
-
-    // Build API routes
-    let api_routes = Router::new()
-        .nest("/novels", routes::novels::router())
-        .nest("/blog", routes::blog::router())
-        .nest("/apps", routes::apps::router())
-        .nest("/auth", routes::auth::router())
-        .with_state(state);
+    let cors = middleware::cors::layer(&state.config.cors);
+    let body_limit = state.config.server.body_limit;
+    let metrics = state.metrics.clone();
+
+    // Without a separate listener, /metrics sits on the main port behind the token
+    let metrics_routes = match (&state.config.metrics.token, &state.config.metrics.bind) {
+        (Some(_), None) => routes::metrics::router(),
+        _ => Router::new(),
+    };
+
+    // Each API version under its own prefix, plus the deprecated unversioned alias
+    let api_routes = ApiVersion::ALL
+        .iter()
+        .fold(Router::new(), |router, &version| {
+            router.nest(version.prefix(), routes::api_router(version))
+        })
+        .nest(
+            api_version::LEGACY_PREFIX,
+            routes::api_router(api_version::LEGACY_VERSION)
+                .layer(axum::middleware::from_fn(api_version::deprecated)),
+        )
+        .with_state(state.clone());
 
     // Main router
     Router::new()
         .route("/", get(root_handler))
-        .route("/health", get(health_handler))
-        .nest("/api", api_routes)
+        .nest(
+            "/health",
+            routes::health::router().with_state(state.clone()),
+        )
+        .merge(api_routes)
+        .merge(SwaggerUi::new("/api/docs").url("/api/openapi.json", openapi::spec()))
+        .nest(
+            "/.well-known",
+            routes::well_known::router().with_state(state.clone()),
+        )
+        .merge(metrics_routes.with_state(state))
         .fallback(handler_404)
+        .layer(axum::middleware::from_fn(middleware::problem::enrich))
+        .layer(axum::middleware::from_fn_with_state(
+            metrics,
+            metrics::track,
+        ))
+        .layer(DefaultBodyLimit::max(body_limit))
         .layer(cors)
         .layer(
             TraceLayer::new_for_http()
-                .make_span_with(DefaultMakeSpan::default().include_headers(true)),
+                .make_span_with(telemetry::make_span)
+                .on_response(telemetry::on_response),
         )
+        .layer(axum::middleware::from_fn(middleware::request_id::assign))
 }
 
 async fn root_handler() -> impl IntoResponse {
@@ -115,29 +270,22 @@ async fn root_handler() -> impl IntoResponse {
         "version": env!("CARGO_PKG_VERSION"),
         "status": "running",
         "endpoints": {
-            "health": "/health",
-            "api": "/api",
-            "novels": "/api/novels",
-            "blog": "/api/blog",
-            "apps": "/api/apps",
-            "auth": "/api/auth"
+            "health": "/health/live",
+            "ready": "/health/ready",
+            "api": "/api/v1",
+            "openapi": "/api/openapi.json",
+            "docs": "/api/docs",
+            "novels": "/api/v1/novels",
+            "blog": "/api/v1/blog",
+            "apps": "/api/v1/apps",
+            "auth": "/api/v1/auth",
+            "admins": "/api/v1/admins",
+            "audit": "/api/v1/audit",
+            "jwks": "/.well-known/jwks.json"
         }
     }))
 }
 
-async fn health_handler() -> impl IntoResponse {
-    Json(json!({
-        "status": "healthy",
-        "timestamp": chrono::Utc::now().to_rfc3339()
-    }))
-}
-
-async fn handler_404() -> impl IntoResponse {
-    (
-        StatusCode::NOT_FOUND,
-        Json(json!({
-            "error": "Not Found",
-            "message": "The requested resource does not exist"
-        })),
-    )
+async fn handler_404() -> AppError {
+    AppError::NotFound(ErrorCode::RouteNotFound)
 }
@@ -0,0 +1,182 @@
+//! Prometheus metrics.
+//!
+//! Request metrics are recorded by [`track`] for every route, labelled with
+//! the matched route pattern rather than the raw path to keep cardinality
+//! bounded. Business counters are bumped by the handlers through `AppState`.
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry, TextEncoder,
+};
+use sqlx::PgPool;
+use std::{sync::Arc, time::Instant};
+
+/// Latency buckets, in seconds
+const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_duration: HistogramVec,
+    pool_connections: IntGaugeVec,
+    chapters_published: IntCounter,
+    logins: IntCounterVec,
+    views: IntCounterVec,
+}
+
+impl Metrics {
+    pub fn new(max_connections: u32) -> anyhow::Result<Self> {
+        let registry = Registry::new();
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests handled"),
+            &["method", "route", "status"],
+        )?;
+        let http_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "Time spent handling HTTP requests",
+            )
+            .buckets(LATENCY_BUCKETS.to_vec()),
+            &["method", "route", "status"],
+        )?;
+        let pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Database pool connections by state"),
+            &["state"],
+        )?;
+        let pool_max_connections = IntGauge::new(
+            "db_pool_max_connections",
+            "Configured database pool size limit",
+        )?;
+        let chapters_published = IntCounter::new(
+            "chapters_published_total",
+            "Chapters created with a publication date",
+        )?;
+        let logins = IntCounterVec::new(
+            Opts::new("logins_total", "Login attempts by outcome"),
+            &["outcome"],
+        )?;
+        let views = IntCounterVec::new(
+            Opts::new("views_recorded_total", "Content views recorded"),
+            &["kind"],
+        )?;
+
+        registry.register(Box::new(http_requests.clone()))?;
+        registry.register(Box::new(http_duration.clone()))?;
+        registry.register(Box::new(pool_connections.clone()))?;
+        registry.register(Box::new(pool_max_connections.clone()))?;
+        registry.register(Box::new(chapters_published.clone()))?;
+        registry.register(Box::new(logins.clone()))?;
+        registry.register(Box::new(views.clone()))?;
+
+        pool_max_connections.set(i64::from(max_connections));
+
+        Ok(Self {
+            registry,
+            http_requests,
+            http_duration,
+            pool_connections,
+            chapters_published,
+            logins,
+            views,
+        })
+    }
+
+    pub fn chapter_published(&self) {
+        self.chapters_published.inc();
+    }
+
+    /// Count a login attempt; `outcome` is the value stored in `login_events`
+    pub fn login(&self, outcome: &str) {
+        self.logins.with_label_values(&[outcome]).inc();
+    }
+
+    /// Count a view of a `novel`, `chapter` or `blog_post`
+    pub fn view(&self, kind: &str) {
+        self.views.with_label_values(&[kind]).inc();
+    }
+
+    /// Render every metric in the Prometheus text format
+    pub fn render(&self, pool: &PgPool) -> anyhow::Result<String> {
+        // sqlx 0.7 doesn't expose the number of tasks waiting for a
+        // connection; `in_use` at `db_pool_max_connections` means they queue
+        let idle = pool.num_idle() as i64;
+        let size = i64::from(pool.size());
+        self.pool_connections.with_label_values(&["idle"]).set(idle);
+        self.pool_connections
+            .with_label_values(&["in_use"])
+            .set(size - idle);
+
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+/// Record the count and latency of every request
+pub async fn track(
+    State(metrics): State<Arc<Metrics>>,
+    matched_path: Option<MatchedPath>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // Unmatched paths share one label so scanners can't blow up the series count
+    let route = matched_path
+        .as_ref()
+        .map_or("unmatched", |path| path.as_str())
+        .to_string();
+    let method = method_label(request.method());
+    let started = Instant::now();
+
+    let response = next.run(request).await;
+
+    let status = response.status().as_u16().to_string();
+    let labels = [method, route.as_str(), status.as_str()];
+    metrics.http_requests.with_label_values(&labels).inc();
+    metrics
+        .http_duration
+        .with_label_values(&labels)
+        .observe(started.elapsed().as_secs_f64());
+
+    response
+}
+
+/// Label for a request method; extension methods share one label for the
+/// same reason unmatched paths do
+fn method_label(method: &Method) -> &'static str {
+    match *method {
+        Method::GET => "GET",
+        Method::HEAD => "HEAD",
+        Method::POST => "POST",
+        Method::PUT => "PUT",
+        Method::PATCH => "PATCH",
+        Method::DELETE => "DELETE",
+        Method::OPTIONS => "OPTIONS",
+        Method::CONNECT => "CONNECT",
+        Method::TRACE => "TRACE",
+        _ => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extension_methods_share_a_label() {
+        assert_eq!(method_label(&Method::GET), "GET");
+        assert_eq!(method_label(&Method::DELETE), "DELETE");
+        for method in ["PROPFIND", "BREW", "X-RANDOM-1234"] {
+            let method = Method::from_bytes(method.as_bytes()).unwrap();
+            assert_eq!(method_label(&method), "other");
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! API versions.
+//!
+//! Each version is served under `/api/v{n}` by a router built for it, which
+//! records the version in the request extensions. Handlers are shared between
+//! versions; where a version changes a response shape, the handler takes
+//! [`ApiVersion`] and picks the shape, so a new version only touches the
+//! handlers it changes.
+//!
+//! The unversioned `/api` prefix is a deprecated alias of v1. Its responses
+//! carry `Deprecation` (RFC 9745), `Sunset` (RFC 8594) and a `Link` to the
+//! same resource under `/api/v1`.
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request},
+    http::{header, request::Parts, HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use chrono::DateTime;
+use std::convert::Infallible;
+
+pub const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+pub const SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+/// Prefix of the deprecated unversioned alias
+pub const LEGACY_PREFIX: &str = "/api";
+
+/// Version served under [`LEGACY_PREFIX`]
+pub const LEGACY_VERSION: ApiVersion = ApiVersion::V1;
+
+/// When the unversioned alias was deprecated, 2026-10-17T00:00:00Z
+const DEPRECATED_AT: i64 = 1792195200;
+
+/// When the unversioned alias stops being served, 2027-04-17T00:00:00Z
+const SUNSET_AT: i64 = 1807920000;
+
+/// Version of the API a request was routed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiVersion {
+    V1,
+}
+
+impl ApiVersion {
+    /// Every version still served, oldest first
+    pub const ALL: &'static [ApiVersion] = &[ApiVersion::V1];
+
+    pub const LATEST: ApiVersion = ApiVersion::V1;
+
+    pub fn prefix(self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "/api/v1",
+        }
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ApiVersion
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<ApiVersion>()
+            .copied()
+            .unwrap_or(LEGACY_VERSION))
+    }
+}
+
+/// Path of an API request with any version segment removed, e.g.
+/// `/api/v1/novels` and `/api/novels` are both `/api/novels`
+pub fn unversioned(path: &str) -> String {
+    ApiVersion::ALL
+        .iter()
+        .find_map(|version| {
+            path.strip_prefix(version.prefix())
+                .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+        .map(|rest| format!("{}{}", LEGACY_PREFIX, rest))
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Mark responses of the unversioned alias as deprecated; must be layered
+/// inside the `/api` nest, where the path no longer has the prefix
+pub async fn deprecated(request: Request, next: Next) -> Response {
+    let successor = format!(
+        "<{}{}>; rel=\"successor-version\"",
+        LEGACY_VERSION.prefix(),
+        request.uri().path()
+    );
+
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+
+    headers.insert(
+        DEPRECATION,
+        HeaderValue::from_str(&format!("@{}", DEPRECATED_AT)).expect("a number"),
+    );
+    if let Some(sunset) = DateTime::from_timestamp(SUNSET_AT, 0) {
+        let date = sunset.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        headers.insert(SUNSET, HeaderValue::from_str(&date).expect("an HTTP date"));
+    }
+    if let Ok(link) = HeaderValue::from_str(&successor) {
+        headers.append(header::LINK, link);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware::from_fn, routing::get, Router};
+    use tower::Service;
+
+    #[test]
+    fn strips_version_segments() {
+        assert_eq!(
+            unversioned("/api/v1/novels/some-novel"),
+            "/api/novels/some-novel"
+        );
+        assert_eq!(unversioned("/api/v1"), "/api");
+        assert_eq!(unversioned("/api/novels"), "/api/novels");
+        assert_eq!(unversioned("/api/v1novels"), "/api/v1novels");
+        assert_eq!(
+            unversioned("/.well-known/jwks.json"),
+            "/.well-known/jwks.json"
+        );
+    }
+
+    #[tokio::test]
+    async fn marks_alias_responses() {
+        let api = Router::new()
+            .route("/novels/:slug", get(|| async { "novel" }))
+            .layer(from_fn(deprecated));
+        let mut app = Router::new().nest(LEGACY_PREFIX, api);
+
+        let response = app
+            .call(
+                Request::builder()
+                    .uri("/api/novels/some-novel?lang=en")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let headers = response.headers();
+
+        assert_eq!(headers[DEPRECATION], "@1792195200");
+        assert_eq!(headers[SUNSET], "Sat, 17 Apr 2027 00:00:00 GMT");
+        assert_eq!(
+            headers[header::LINK],
+            "</api/v1/novels/some-novel>; rel=\"successor-version\""
+        );
+    }
+}
@@ -1,26 +1,68 @@
 use axum::{
     async_trait,
-    extract::{FromRequestParts, State},
-    http::{header, request::Parts, StatusCode},
-    response::{IntoResponse, Response},
-    Json, RequestPartsExt,
+    extract::FromRequestParts,
+    http::{header, request::Parts},
 };
-use jsonwebtoken::{decode, DecodingKey, Validation};
-use serde_json::json;
+use chrono::{DateTime, TimeZone, Utc};
+use jsonwebtoken::Validation;
+use std::marker::PhantomData;
 use uuid::Uuid;
 
-use crate::{db::AppState, error::AppError, models::Claims};
+use crate::{
+    auth::tokens::{hash_token, API_TOKEN_PREFIX},
+    db::AppState,
+    error::{AppError, ErrorCode},
+    models::{Claims, Role, Scope},
+};
 
 /// Authenticated user extractor
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct AuthUser {
     pub user_id: Uuid,
     pub username: String,
+    pub role: Role,
+    pub credential: Credential,
+}
+
+/// How a request was authenticated
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub enum Credential {
+    /// Access token from an interactive login
+    Session {
+        token_id: Uuid,
+        /// Refresh token family; absent in tokens issued before sessions were tracked
+        session_id: Option<Uuid>,
+        expires_at: DateTime<Utc>,
+    },
+    /// Personal access token, limited to its scopes
+    ApiToken { token_id: Uuid, scopes: Vec<Scope> },
+}
+
+impl AuthUser {
+    /// Require a scope; login sessions implicitly have every scope
+    pub fn require_scope(&self, scope: Scope) -> Result<(), AppError> {
+        match &self.credential {
+            Credential::ApiToken { scopes, .. } if !scopes.contains(&scope) => {
+                Err(AppError::MissingScope(scope))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Require an interactive login, e.g. for account and token management
+    pub fn require_session(&self) -> Result<(), AppError> {
+        match self.credential {
+            Credential::Session { .. } => Ok(()),
+            Credential::ApiToken { .. } => Err(AppError::Forbidden(ErrorCode::SessionRequired)),
+        }
+    }
 }
 
 #[async_trait]
 impl FromRequestParts<AppState> for AuthUser {
-    type Rejection = Response;
+    type Rejection = AppError;
 
     async fn from_request_parts(
         parts: &mut Parts,
@@ -31,62 +73,107 @@ impl FromRequestParts<AppState> for AuthUser {
             .headers
             .get(header::AUTHORIZATION)
             .and_then(|value| value.to_str().ok())
-            .ok_or_else(|| {
-                (
-                    StatusCode::UNAUTHORIZED,
-                    Json(json!({
-                        "error": "Missing authorization header"
-                    })),
-                )
-                    .into_response()
-            })?;
+            .ok_or(AppError::Unauthorized)?;
 
         // Check if it's a Bearer token
-        let token = auth_header.strip_prefix("Bearer ").ok_or_else(|| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({
-                    "error": "Invalid authorization header format"
-                })),
-            )
-                .into_response()
-        })?;
+        let token = auth_header
+            .strip_prefix("Bearer ")
+            .ok_or(AppError::InvalidToken)?;
 
-        // Decode and validate JWT
-        let token_data = decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(state.config.jwt_secret.as_bytes()),
-            &Validation::default(),
-        )
+        if token.starts_with(API_TOKEN_PREFIX) {
+            authenticate_api_token(state, token).await
+        } else {
+            authenticate_jwt(state, token).await
+        }
+    }
+}
+
+async fn authenticate_jwt(state: &AppState, token: &str) -> Result<AuthUser, AppError> {
+    // Decode and validate JWT
+    let claims = state
+        .jwt_keys
+        .decode::<Claims>(token, Validation::default())
         .map_err(|e| {
             tracing::warn!("JWT validation failed: {:?}", e);
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({
-                    "error": "Invalid or expired token"
-                })),
-            )
-                .into_response()
+            AppError::from(e)
         })?;
 
-        let claims = token_data.claims;
-
-        // Parse user ID from claims
-        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| {
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({
-                    "error": "Invalid user ID in token"
-                })),
-            )
-                .into_response()
-        })?;
+    // Parse user ID from claims
+    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| AppError::InvalidToken)?;
+
+    let token_id = Uuid::parse_str(&claims.jti).map_err(|_| AppError::InvalidToken)?;
 
-        Ok(AuthUser {
-            user_id,
-            username: claims.username,
-        })
+    // Reject tokens that were revoked before their expiry
+    if state
+        .revocations
+        .is_revoked(token_id, user_id, claims.iat)
+        .await
+    {
+        return Err(AppError::InvalidToken);
     }
+
+    let expires_at = Utc
+        .timestamp_opt(claims.exp, 0)
+        .single()
+        .ok_or(AppError::InvalidToken)?;
+
+    let session_id = claims
+        .sid
+        .as_deref()
+        .map(Uuid::parse_str)
+        .transpose()
+        .map_err(|_| AppError::InvalidToken)?;
+
+    Ok(AuthUser {
+        user_id,
+        username: claims.username,
+        role: claims.role,
+        credential: Credential::Session {
+            token_id,
+            session_id,
+            expires_at,
+        },
+    })
+}
+
+async fn authenticate_api_token(state: &AppState, token: &str) -> Result<AuthUser, AppError> {
+    // The token acts with its owner's current role, narrowed to its scopes
+    let row: Option<(Uuid, Uuid, String, Role, Vec<String>)> = sqlx::query_as(
+        "SELECT t.id, a.id, a.username, a.role, t.scopes
+         FROM api_tokens t
+         JOIN admins a ON a.id = t.admin_id
+         WHERE t.token_hash = $1
+           AND t.revoked_at IS NULL
+           AND (t.expires_at IS NULL OR t.expires_at > NOW())
+           AND a.disabled_at IS NULL",
+    )
+    .bind(hash_token(token))
+    .fetch_optional(&state.pool)
+    .await?;
+
+    let (token_id, user_id, username, role, scopes) = row.ok_or(AppError::InvalidToken)?;
+
+    // Only touch the row once a minute so busy scripts don't write on every request
+    if let Err(e) = sqlx::query(
+        "UPDATE api_tokens SET last_used_at = NOW()
+         WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute')",
+    )
+    .bind(token_id)
+    .execute(&state.pool)
+    .await
+    {
+        tracing::warn!("Failed to record API token use: {:?}", e);
+    }
+
+    Ok(AuthUser {
+        user_id,
+        username,
+        role,
+        credential: Credential::ApiToken {
+            token_id,
+            scopes: scopes.iter().filter_map(|s| Scope::parse(s)).collect(),
+        },
+    })
 }
 
 /// Optional authenticated user extractor (doesn't fail if no auth)
@@ -95,7 +182,7 @@ pub struct OptionalAuthUser(pub Option<AuthUser>);
 
 #[async_trait]
 impl FromRequestParts<AppState> for OptionalAuthUser {
-    type Rejection = Response;
+    type Rejection = AppError;
 
     async fn from_request_parts(
         parts: &mut Parts,
@@ -107,3 +194,49 @@ impl FromRequestParts<AppState> for OptionalAuthUser {
         }
     }
 }
+
+/// Minimum role required by a [`RequireRole`] extractor
+pub trait RoleRequirement {
+    const ROLE: Role;
+}
+
+pub struct Owner;
+pub struct Editor;
+pub struct Author;
+
+impl RoleRequirement for Owner {
+    const ROLE: Role = Role::Owner;
+}
+
+impl RoleRequirement for Editor {
+    const ROLE: Role = Role::Editor;
+}
+
+impl RoleRequirement for Author {
+    const ROLE: Role = Role::Author;
+}
+
+/// Authenticated user extractor that also requires at least role `R`,
+/// e.g. `RequireRole<Editor>` admits editors and owners
+pub struct RequireRole<R>(pub AuthUser, pub PhantomData<R>);
+
+#[async_trait]
+impl<R> FromRequestParts<AppState> for RequireRole<R>
+where
+    R: RoleRequirement + Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let user = AuthUser::from_request_parts(parts, state).await?;
+
+        if user.role < R::ROLE {
+            return Err(AppError::Forbidden(ErrorCode::InsufficientRole));
+        }
+
+        Ok(RequireRole(user, PhantomData))
+    }
+}
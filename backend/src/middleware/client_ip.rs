@@ -0,0 +1,77 @@
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::request::Parts,
+};
+use std::{convert::Infallible, net::IpAddr, net::SocketAddr};
+
+use crate::db::AppState;
+
+/// Client IP address extractor.
+///
+/// Uses the peer address of the connection, or the `Fly-Client-IP` header
+/// when `server.trust_fly_proxy` says the fly.io proxy sets it. Anywhere else
+/// any client could set that header, as it can `X-Forwarded-For`, which is
+/// never used.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+#[async_trait]
+impl FromRequestParts<AppState> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(ClientIp(client_ip(
+            parts,
+            state.config.server.trust_fly_proxy,
+        )))
+    }
+}
+
+fn client_ip(parts: &Parts, trust_fly_proxy: bool) -> Option<IpAddr> {
+    let from_header = parts
+        .headers
+        .get("fly-client-ip")
+        .filter(|_| trust_fly_proxy)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<IpAddr>().ok());
+
+    let from_connection = parts
+        .extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+
+    from_header.or(from_connection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+
+    fn parts(fly_client_ip: Option<&str>) -> Parts {
+        let mut request = Request::builder();
+        if let Some(ip) = fly_client_ip {
+            request = request.header("fly-client-ip", ip);
+        }
+        let mut request = request.body(()).unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000))));
+        request.into_parts().0
+    }
+
+    #[test]
+    fn header_is_ignored_unless_the_proxy_is_trusted() {
+        let peer: IpAddr = [10, 0, 0, 1].into();
+        let client: IpAddr = [203, 0, 113, 7].into();
+
+        assert_eq!(client_ip(&parts(Some("203.0.113.7")), false), Some(peer));
+        assert_eq!(client_ip(&parts(Some("203.0.113.7")), true), Some(client));
+        assert_eq!(client_ip(&parts(Some("garbage")), true), Some(peer));
+        assert_eq!(client_ip(&parts(None), true), Some(peer));
+    }
+}
@@ -0,0 +1,152 @@
+//! `Json`, `Path` and `Query` with problem responses.
+//!
+//! They wrap axum's extractors so a malformed body, path or query is
+//! rejected with an `AppError` and its stable code instead of axum's plain
+//! text. Handlers use these in place of axum's; `Json` also serializes
+//! responses.
+
+use axum::{
+    extract::{FromRequest, FromRequestParts},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::error::AppError;
+
+#[derive(Debug, Clone, Copy, Default, FromRequest)]
+#[from_request(via(axum::Json), rejection(AppError))]
+pub struct Json<T>(pub T);
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+#[derive(Debug, FromRequestParts)]
+#[from_request(via(axum::extract::Path), rejection(AppError))]
+pub struct Path<T>(pub T);
+
+#[derive(Debug, FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(AppError))]
+pub struct Query<T>(pub T);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+        http::{header, StatusCode},
+        routing::post,
+        Router,
+    };
+    use serde::Deserialize;
+    use serde_json::Value;
+    use tower::Service;
+    use uuid::Uuid;
+
+    use crate::error::PROBLEM_CONTENT_TYPE;
+
+    #[derive(Deserialize)]
+    struct NewItem {
+        #[allow(dead_code)]
+        title: String,
+    }
+
+    #[derive(Deserialize)]
+    struct Params {
+        #[allow(dead_code)]
+        limit: i64,
+    }
+
+    async fn handler(
+        Path(_): Path<Uuid>,
+        Query(_): Query<Params>,
+        Json(_): Json<NewItem>,
+    ) -> Json<&'static str> {
+        Json("ok")
+    }
+
+    async fn send(uri: &str, content_type: &str, body: &'static str) -> (StatusCode, Value) {
+        let mut app = Router::new().route("/items/:id", post(handler));
+        let response = app
+            .call(
+                Request::post(uri)
+                    .header(header::CONTENT_TYPE, content_type)
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let status = response.status();
+        if !status.is_success() {
+            assert_eq!(
+                response.headers()[header::CONTENT_TYPE],
+                PROBLEM_CONTENT_TYPE
+            );
+        }
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    const ID: &str = "/items/3f2b6c1e-8d1a-4c55-9b1e-2a7f0e9d4c11";
+
+    #[tokio::test]
+    async fn accepts_well_formed_requests() {
+        let (status, body) = send(
+            &format!("{}?limit=1", ID),
+            "application/json",
+            r#"{"title": "t"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "ok");
+    }
+
+    #[tokio::test]
+    async fn malformed_json_is_a_problem() {
+        let uri = format!("{}?limit=1", ID);
+
+        for (content_type, body, code) in [
+            ("application/json", r#"{"title": "#, "request.invalid_body"),
+            (
+                "application/json",
+                r#"{"name": "t"}"#,
+                "request.invalid_body",
+            ),
+            (
+                "text/plain",
+                r#"{"title": "t"}"#,
+                "request.json_content_type",
+            ),
+        ] {
+            let (status, problem) = send(&uri, content_type, body).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+            assert_eq!(problem["code"], code);
+            assert_eq!(problem["status"], 400);
+        }
+    }
+
+    #[tokio::test]
+    async fn malformed_path_and_query_are_problems() {
+        let (status, problem) = send(
+            "/items/not-a-uuid?limit=1",
+            "application/json",
+            r#"{"title": "t"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(problem["code"], "request.invalid_path");
+
+        let (status, problem) = send(
+            &format!("{}?limit=many", ID),
+            "application/json",
+            r#"{"title": "t"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(problem["code"], "request.invalid_query");
+    }
+}
@@ -0,0 +1,161 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderMap, HeaderValue, Uri},
+    response::{IntoResponseParts, ResponseParts},
+};
+use std::convert::Infallible;
+use utoipa::{
+    openapi::{
+        path::{Parameter, ParameterBuilder, ParameterIn},
+        schema::{ObjectBuilder, Type},
+        Required,
+    },
+    IntoParams,
+};
+
+use crate::i18n::Locale;
+
+/// Response language, from `?lang=` or else `Accept-Language`, defaulting to Korean
+///
+/// As a response part it sets `Content-Language` and varies the response on
+/// `Accept-Language`.
+#[derive(Debug, Clone, Copy)]
+pub struct Lang(pub Locale);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Lang
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Lang(negotiate(&parts.uri, &parts.headers)))
+    }
+}
+
+impl IntoResponseParts for Lang {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        let headers = res.headers_mut();
+        headers.insert(
+            header::CONTENT_LANGUAGE,
+            HeaderValue::from_static(self.0.as_str()),
+        );
+        headers.append(header::VARY, HeaderValue::from_static("accept-language"));
+        Ok(res)
+    }
+}
+
+impl IntoParams for Lang {
+    fn into_params(_: impl Fn() -> Option<ParameterIn>) -> Vec<Parameter> {
+        let tags = ObjectBuilder::new()
+            .schema_type(Type::String)
+            .enum_values(Some(Locale::ALL.iter().map(|locale| locale.as_str())));
+
+        vec![
+            ParameterBuilder::new()
+                .name("lang")
+                .parameter_in(ParameterIn::Query)
+                .required(Required::False)
+                .description(Some("Response language, overrides `Accept-Language`"))
+                .schema(Some(tags))
+                .build(),
+            ParameterBuilder::new()
+                .name("Accept-Language")
+                .parameter_in(ParameterIn::Header)
+                .required(Required::False)
+                .schema(Some(ObjectBuilder::new().schema_type(Type::String)))
+                .build(),
+        ]
+    }
+}
+
+/// Pick the response language for a request
+pub fn negotiate(uri: &Uri, headers: &HeaderMap) -> Locale {
+    let from_query = uri.query().and_then(|query| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(name, _)| *name == "lang")
+            .and_then(|(_, value)| Locale::from_tag(value))
+    });
+
+    from_query
+        .or_else(|| {
+            headers
+                .get(header::ACCEPT_LANGUAGE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(from_accept_language)
+        })
+        .unwrap_or_default()
+}
+
+/// Best supported language in an `Accept-Language` header, by quality
+fn from_accept_language(value: &str) -> Option<Locale> {
+    let mut ranges: Vec<(f32, &str)> = value
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let tag = parts.next()?.trim();
+            let quality = match parts.find_map(|param| param.trim().strip_prefix("q=")) {
+                Some(quality) => quality.trim().parse().ok()?,
+                None => 1.0,
+            };
+            Some((quality, tag))
+        })
+        .filter(|(quality, _)| *quality > 0.0)
+        .collect();
+
+    // Stable, so equal qualities keep the client's order
+    ranges.sort_by(|a, b| b.0.total_cmp(&a.0));
+    ranges
+        .into_iter()
+        .find_map(|(_, tag)| Locale::from_tag(tag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn negotiate_with(uri: &str, accept_language: Option<&str>) -> Locale {
+        let mut headers = HeaderMap::new();
+        if let Some(value) = accept_language {
+            headers.insert(
+                header::ACCEPT_LANGUAGE,
+                HeaderValue::from_str(value).unwrap(),
+            );
+        }
+        negotiate(&uri.parse().unwrap(), &headers)
+    }
+
+    #[test]
+    fn defaults_to_korean() {
+        assert_eq!(negotiate_with("/api/novels", None), Locale::Ko);
+        assert_eq!(
+            negotiate_with("/api/novels", Some("ja, fr;q=0.5")),
+            Locale::Ko
+        );
+        assert_eq!(negotiate_with("/api/novels", Some("*")), Locale::Ko);
+    }
+
+    #[test]
+    fn follows_accept_language_quality() {
+        assert_eq!(
+            negotiate_with("/", Some("en-US,en;q=0.9,ko;q=0.8")),
+            Locale::En
+        );
+        assert_eq!(negotiate_with("/", Some("en;q=0.5, ko-KR")), Locale::Ko);
+        assert_eq!(negotiate_with("/", Some("ja, en;q=0.3")), Locale::En);
+        assert_eq!(negotiate_with("/", Some("en;q=0, ko;q=0.1")), Locale::Ko);
+    }
+
+    #[test]
+    fn query_overrides_header() {
+        assert_eq!(negotiate_with("/?lang=en", Some("ko")), Locale::En);
+        assert_eq!(negotiate_with("/?page=2&lang=ko", Some("en")), Locale::Ko);
+        assert_eq!(negotiate_with("/?lang=ja", Some("en")), Locale::En);
+    }
+}
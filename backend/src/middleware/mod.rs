@@ -1 +1,9 @@
+pub mod api_version;
 pub mod auth;
+pub mod client_ip;
+pub mod cors;
+pub mod extract;
+pub mod locale;
+pub mod problem;
+pub mod request_id;
+pub mod request_meta;
@@ -0,0 +1,146 @@
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+
+use super::{locale::negotiate, request_id::RequestId};
+use crate::error::{ErrorCode, Problem, PROBLEM_CONTENT_TYPE};
+
+/// Localize problem responses and add the request id, so reports can be
+/// matched to logs
+pub async fn enrich(request: Request, next: Next) -> Response {
+    let locale = negotiate(request.uri(), request.headers());
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|RequestId(id)| id.clone());
+    let mut response = next.run(request).await;
+
+    // The router answers these itself, with an empty body
+    if response.status() == StatusCode::METHOD_NOT_ALLOWED
+        && response.extensions().get::<Problem>().is_none()
+    {
+        let (mut parts, _) = response.into_parts();
+        parts.headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(PROBLEM_CONTENT_TYPE),
+        );
+        parts
+            .extensions
+            .insert(Problem::new(parts.status, ErrorCode::MethodNotAllowed));
+        response = Response::from_parts(parts, Body::empty());
+    }
+
+    if let Some(problem) = response.extensions_mut().get_mut::<Problem>() {
+        problem.localize(locale);
+        problem.request_id = request_id;
+
+        match serde_json::to_vec(problem) {
+            Ok(body) => {
+                response.headers_mut().remove(header::CONTENT_LENGTH);
+                *response.body_mut() = Body::from(body);
+            }
+            Err(e) => tracing::warn!("Failed to serialize problem: {:?}", e),
+        }
+
+        let headers = response.headers_mut();
+        headers.insert(
+            header::CONTENT_LANGUAGE,
+            HeaderValue::from_static(locale.as_str()),
+        );
+        headers.append(header::VARY, HeaderValue::from_static("accept-language"));
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::to_bytes,
+        middleware::from_fn,
+        routing::{get, post},
+        Router,
+    };
+    use serde_json::Value;
+    use tower::Service;
+
+    use crate::middleware::{extract::Json, request_id};
+
+    async fn send(request: Request) -> (Response, Value) {
+        let mut app = Router::new()
+            .route("/items", get(|| async { "items" }))
+            .route(
+                "/echo",
+                post(|Json(body): Json<Value>| async { Json(body) }),
+            )
+            .layer(from_fn(enrich))
+            .layer(from_fn(request_id::assign));
+
+        let response = app.call(request).await.unwrap();
+        let (parts, body) = response.into_parts();
+        let body = to_bytes(body, usize::MAX).await.unwrap();
+        (
+            Response::from_parts(parts, Body::empty()),
+            serde_json::from_slice(&body).unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn rejections_carry_the_request_id() {
+        let (response, problem) = send(
+            Request::post("/echo")
+                .header(header::CONTENT_TYPE, "application/json")
+                .header("x-request-id", "client-id-1")
+                .body(Body::from("{"))
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(problem["code"], "request.invalid_body");
+        assert_eq!(problem["request_id"], "client-id-1");
+    }
+
+    #[tokio::test]
+    async fn localized_problems_vary_by_language() {
+        let (response, problem) = send(
+            Request::delete("/items")
+                .header(header::ACCEPT_LANGUAGE, "en")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(problem["code"], "route.method_not_allowed");
+        assert_eq!(response.headers()[header::CONTENT_LANGUAGE], "en");
+        assert_eq!(response.headers()[header::VARY], "accept-language");
+    }
+
+    #[tokio::test]
+    async fn method_not_allowed_is_a_problem() {
+        let (response, problem) = send(
+            Request::delete("/items?lang=en")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            PROBLEM_CONTENT_TYPE
+        );
+        assert!(response.headers().contains_key(header::ALLOW));
+        assert_eq!(problem["code"], "route.method_not_allowed");
+        assert_eq!(
+            problem["detail"],
+            "This method is not allowed for the requested resource"
+        );
+        assert!(problem["request_id"].is_string());
+    }
+}
@@ -0,0 +1,77 @@
+//! Request ids.
+//!
+//! Every request gets an id, taken from `X-Request-Id` (or the fly.io
+//! proxy's `Fly-Request-Id`) when the client sent a usable one and generated
+//! otherwise. It is recorded on the request's tracing span, so handler and
+//! sqlx logs carry it, echoed in the `X-Request-Id` response header and
+//! included in problem responses.
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use uuid::Uuid;
+
+pub const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Headers an incoming id is accepted from, in order of preference
+const INCOMING_HEADERS: &[&str] = &["x-request-id", "fly-request-id"];
+
+const MAX_LEN: usize = 100;
+
+/// Id of the current request, set by [`assign`]
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+/// Assign the request id; must wrap the trace layer so the span can record it
+pub async fn assign(mut request: Request, next: Next) -> Response {
+    let id = INCOMING_HEADERS
+        .iter()
+        .find_map(|name| request.headers().get(*name))
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| is_valid(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    // Only visible ASCII is accepted above, so this can't fail
+    let value = HeaderValue::from_str(&id).ok();
+
+    request.extensions_mut().insert(RequestId(id));
+    let mut response = next.run(request).await;
+
+    if let Some(value) = value {
+        response.headers_mut().insert(X_REQUEST_ID, value);
+    }
+
+    response
+}
+
+/// Client ids end up in logs and audit entries, so only plain tokens are kept
+fn is_valid(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_LEN
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_plain_ids() {
+        assert!(is_valid("3f2b6c1e-8d1a-4c55-9b1e-2a7f0e9d4c11"));
+        assert!(is_valid("01HF8Z3K2M:web.1_retry"));
+    }
+
+    #[test]
+    fn rejects_unsafe_ids() {
+        assert!(!is_valid(""));
+        assert!(!is_valid("id with spaces"));
+        assert!(!is_valid("id\"}, {\"forged\": true"));
+        assert!(!is_valid(&"a".repeat(MAX_LEN + 1)));
+    }
+}
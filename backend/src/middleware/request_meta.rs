@@ -0,0 +1,46 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts},
+};
+use std::{convert::Infallible, net::IpAddr};
+
+use super::{client_ip::ClientIp, request_id::RequestId};
+use crate::db::AppState;
+
+/// Request metadata recorded alongside audit entries and sessions
+#[derive(Debug, Clone, Default)]
+pub struct RequestMeta {
+    pub request_id: Option<String>,
+    pub ip: Option<IpAddr>,
+    pub user_agent: Option<String>,
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for RequestMeta {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let request_id = parts
+            .extensions
+            .get::<RequestId>()
+            .map(|RequestId(id)| id.clone());
+
+        let user_agent = parts
+            .headers
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(512).collect());
+
+        let ClientIp(ip) = ClientIp::from_request_parts(parts, state).await?;
+
+        Ok(RequestMeta {
+            request_id,
+            ip,
+            user_agent,
+        })
+    }
+}
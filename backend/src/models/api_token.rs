@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Permission granted to a personal access token; content is publicly
+/// readable, so only writes are scoped
+#[allow(clippy::enum_variant_names)] // named after the scope strings
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub enum Scope {
+    #[serde(rename = "novels:write")]
+    NovelsWrite,
+    #[serde(rename = "blog:write")]
+    BlogWrite,
+    #[serde(rename = "apps:write")]
+    AppsWrite,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::NovelsWrite => "novels:write",
+            Scope::BlogWrite => "blog:write",
+            Scope::AppsWrite => "apps:write",
+        }
+    }
+
+    /// Parse a scope as stored in the database; unknown scopes grant nothing
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "novels:write" => Some(Scope::NovelsWrite),
+            "blog:write" => Some(Scope::BlogWrite),
+            "apps:write" => Some(Scope::AppsWrite),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Personal access token, without its secret
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Create personal access token request
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateApiToken {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(min = 1))]
+    pub scopes: Vec<Scope>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Newly created token; the secret is only ever returned here
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedApiToken {
+    pub token: String,
+    #[serde(flatten)]
+    pub info: ApiToken,
+}
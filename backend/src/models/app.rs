@@ -1,64 +1,69 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::i18n::{self, Locale};
+
 /// Platform types for apps
-pub const PLATFORMS: &[(&str, &str)] = &[
-    ("ios", "iOS"),
-    ("android", "Android"),
-    ("web", "Web"),
-    ("windows", "Windows"),
-    ("macos", "macOS"),
-    ("linux", "Linux"),
-    ("game", "Game"),
-];
+pub const PLATFORMS: &[&str] = &["ios", "android", "web", "windows", "macos", "linux", "game"];
 
 /// Distribution channel types
-pub const DISTRIBUTION_CHANNELS: &[(&str, &str)] = &[
-    ("app_store", "App Store"),
-    ("play_store", "Google Play"),
-    ("web", "Web App"),
-    ("steam", "Steam"),
-    ("stove", "Stove"),
-    ("epic", "Epic Games"),
-    ("gog", "GOG"),
-    ("itch", "itch.io"),
-    ("landing_page", "Landing Page"),
-    ("direct_download", "Direct Download"),
-    ("github", "GitHub Releases"),
-    ("other", "Other"),
+pub const DISTRIBUTION_CHANNELS: &[&str] = &[
+    "app_store",
+    "play_store",
+    "web",
+    "steam",
+    "stove",
+    "epic",
+    "gog",
+    "itch",
+    "landing_page",
+    "direct_download",
+    "github",
+    "other",
 ];
 
+/// Platform info for API
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PlatformInfo {
+    pub id: &'static str,
+    pub name: &'static str,
+}
+
 /// Get all available platforms
-pub fn get_all_platforms() -> Vec<serde_json::Value> {
+pub fn get_all_platforms(locale: Locale) -> Vec<PlatformInfo> {
     PLATFORMS
         .iter()
-        .map(|(id, name)| {
-            serde_json::json!({
-                "id": id,
-                "name": name
-            })
+        .map(|id| PlatformInfo {
+            id,
+            name: i18n::text(locale, &format!("platform.{}", id)),
         })
         .collect()
 }
 
+/// Distribution channel info for API
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChannelInfo {
+    pub id: &'static str,
+    pub name: &'static str,
+}
+
 /// Get all available distribution channels
-pub fn get_all_distribution_channels() -> Vec<serde_json::Value> {
+pub fn get_all_distribution_channels(locale: Locale) -> Vec<ChannelInfo> {
     DISTRIBUTION_CHANNELS
         .iter()
-        .map(|(id, name)| {
-            serde_json::json!({
-                "id": id,
-                "name": name
-            })
+        .map(|id| ChannelInfo {
+            id,
+            name: i18n::text(locale, &format!("channel.{}", id)),
         })
         .collect()
 }
 
 /// App model
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct App {
     pub id: Uuid,
     pub name: String,
@@ -66,6 +71,7 @@ pub struct App {
     pub description: Option<String>,
     pub platforms: Vec<String>, // ["ios", "android", "web", "windows", "macos", "linux", "game"]
     pub screenshots: Vec<String>,
+    #[schema(value_type = Vec<DistributionChannel>)]
     pub distribution_channels: serde_json::Value, // JSON array: [{"type": "app_store", "url": "...", "label": "..."}]
     pub privacy_policy_url: Option<String>,
     pub created_at: DateTime<Utc>,
@@ -73,7 +79,7 @@ pub struct App {
 }
 
 /// Distribution channel entry
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DistributionChannel {
     #[serde(rename = "type")]
     pub channel_type: String, // app_store, play_store, web, steam, stove, etc.
@@ -82,12 +88,13 @@ pub struct DistributionChannel {
 }
 
 /// Create app request
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateApp {
     #[validate(length(min = 1, max = 255))]
     pub name: String,
 
     // Ignored: slug is always auto-generated from UUID
+    #[allow(dead_code)]
     pub slug: Option<String>,
 
     pub description: Option<String>,
@@ -104,7 +111,7 @@ pub struct CreateApp {
 }
 
 /// Update app request
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateApp {
     #[validate(length(min = 1, max = 255))]
     pub name: Option<String>,
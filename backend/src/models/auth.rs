@@ -1,21 +1,69 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 use validator::Validate;
 
+use super::api_token::Scope;
+
+/// Admin role, ordered from least to most privileged
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema, sqlx::Type,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum Role {
+    Viewer, // read-only access to drafts and admin views
+    Author, // create and edit content
+    Editor, // delete content and manage apps
+    Owner,  // manage other accounts
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::Viewer => write!(f, "viewer"),
+            Role::Author => write!(f, "author"),
+            Role::Editor => write!(f, "editor"),
+            Role::Owner => write!(f, "owner"),
+        }
+    }
+}
+
 /// Admin user model
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Admin {
     pub id: Uuid,
     pub username: String,
     #[serde(skip_serializing)]
     pub password_hash: String,
+    pub role: Role,
+    pub disabled_at: Option<DateTime<Utc>>,
+    pub totp_enabled_at: Option<DateTime<Utc>>,
+    pub locked_until: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
 
+impl Admin {
+    pub fn is_disabled(&self) -> bool {
+        self.disabled_at.is_some()
+    }
+
+    pub fn has_totp(&self) -> bool {
+        self.totp_enabled_at.is_some()
+    }
+
+    /// Remaining lockout after too many failed logins, if any
+    pub fn locked_for(&self) -> Option<chrono::Duration> {
+        self.locked_until
+            .map(|until| until - Utc::now())
+            .filter(|remaining| *remaining > chrono::Duration::zero())
+    }
+}
+
 /// Login request
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct LoginRequest {
     #[validate(length(min = 3, max = 100))]
     pub username: String,
@@ -25,18 +73,117 @@ pub struct LoginRequest {
 }
 
 /// Login response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct LoginResponse {
     pub token: String,
     pub expires_at: DateTime<Utc>,
+    pub refresh_token: String,
+    pub refresh_expires_at: DateTime<Utc>,
     pub user: AdminInfo,
 }
 
-/// Admin info (without password)
+/// Returned instead of tokens when the account has 2FA enabled
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MfaChallenge {
+    pub mfa_required: bool,
+    pub mfa_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Result of the password step of login
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum LoginOutcome {
+    Authenticated(LoginResponse),
+    MfaRequired(MfaChallenge),
+}
+
+/// Second login step: exchange the mfa token and a TOTP or recovery code
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct MfaLoginRequest {
+    #[validate(length(min = 1))]
+    pub mfa_token: String,
+
+    pub code: Option<String>,
+
+    pub recovery_code: Option<String>,
+}
+
+/// Claims of the short-lived "mfa pending" token
 #[derive(Debug, Serialize, Deserialize)]
+pub struct MfaClaims {
+    pub sub: String, // admin id
+    pub aud: String,
+    pub jti: String,
+    pub exp: i64,
+    pub iat: f64,
+}
+
+/// TOTP enrollment response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TotpSetupResponse {
+    pub secret: String,
+    pub provisioning_uri: String,
+}
+
+/// TOTP code submitted to confirm enrollment
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct TotpConfirmRequest {
+    #[validate(length(equal = 6))]
+    pub code: String,
+}
+
+/// Recovery codes, shown once after enrollment
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+/// Disable 2FA request; requires the password and a current code
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct TotpDisableRequest {
+    #[validate(length(min = 1))]
+    pub password: String,
+
+    pub code: Option<String>,
+
+    pub recovery_code: Option<String>,
+}
+
+/// Refresh / logout request
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct RefreshRequest {
+    #[validate(length(min = 1))]
+    pub refresh_token: String,
+}
+
+/// Stored refresh token (the token itself is never stored, only its hash)
+#[derive(Debug, Clone, FromRow)]
+pub struct RefreshToken {
+    pub id: Uuid,
+    pub admin_id: Uuid,
+    pub family_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Admin info (without password)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct AdminInfo {
     pub id: Uuid,
     pub username: String,
+    pub role: Role,
+}
+
+impl From<Admin> for AdminInfo {
+    fn from(admin: Admin) -> Self {
+        Self {
+            role: admin.role,
+            id: admin.id,
+            username: admin.username,
+        }
+    }
 }
 
 /// JWT Claims
@@ -44,18 +191,161 @@ pub struct AdminInfo {
 pub struct Claims {
     pub sub: String, // user id
     pub username: String,
-    pub exp: i64, // expiration timestamp
-    pub iat: i64, // issued at timestamp
+    pub role: Role,
+    pub jti: String, // unique token id, used for revocation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>, // refresh token family the token was issued for
+    pub exp: i64,    // expiration timestamp
+    pub iat: f64,    // issued at, with milliseconds so revocation cutoffs are exact
 }
 
 impl Claims {
-    pub fn new(admin_id: Uuid, username: String, expiration_seconds: i64) -> Self {
-        let now = Utc::now().timestamp();
+    pub fn new(
+        admin_id: Uuid,
+        username: String,
+        role: Role,
+        session_id: Uuid,
+        expiration_seconds: i64,
+    ) -> Self {
+        let now = Utc::now();
         Self {
             sub: admin_id.to_string(),
             username,
-            exp: now + expiration_seconds,
-            iat: now,
+            role,
+            jti: Uuid::new_v4().to_string(),
+            sid: Some(session_id.to_string()),
+            exp: now.timestamp() + expiration_seconds,
+            iat: issued_at(now),
         }
     }
 }
+
+/// `iat` of a token issued at `at`: a NumericDate kept to the millisecond,
+/// since a whole second would let tokens issued just before a revocation
+/// cutoff through
+pub fn issued_at(at: DateTime<Utc>) -> f64 {
+    at.timestamp_millis() as f64 / 1000.0
+}
+
+/// The caller's own account, as returned by `/auth/me`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CurrentAdmin {
+    #[serde(flatten)]
+    pub admin: AdminInfo,
+    pub totp_enabled: bool,
+    pub created_at: DateTime<Utc>,
+    /// "session" or "api_token"
+    pub auth_method: &'static str,
+    /// Scopes of the API token, when authenticated with one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<Scope>>,
+}
+
+/// Change password request
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ChangePasswordRequest {
+    #[validate(length(min = 1))]
+    pub current_password: String,
+
+    #[validate(length(min = 6))]
+    pub new_password: String,
+}
+
+/// Active login, i.e. a refresh token family that can still be refreshed
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct SessionInfo {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    /// Last login or refresh in this session
+    pub last_seen_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    #[sqlx(default)]
+    pub current: bool,
+}
+
+/// Revoke-all request; `before` defaults to now
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RevokeAllRequest {
+    pub before: Option<DateTime<Utc>>,
+}
+
+/// Admin account as listed to owners
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct AdminSummary {
+    pub id: Uuid,
+    pub username: String,
+    pub role: Role,
+    pub disabled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Create invitation request
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateInvitation {
+    pub role: Role,
+}
+
+/// Created invitation; the token is only ever shown once
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InvitationResponse {
+    pub id: Uuid,
+    pub token: String,
+    pub role: Role,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Accept invitation request
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct AcceptInvitation {
+    #[validate(length(min = 1))]
+    pub token: String,
+
+    #[validate(length(min = 3, max = 100))]
+    pub username: String,
+
+    #[validate(length(min = 6))]
+    pub password: String,
+}
+
+/// Change role request
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateRole {
+    pub role: Role,
+}
+
+/// Where to send the browser to sign in with an OIDC provider
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OidcAuthorizeResponse {
+    pub authorization_url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// OIDC providers admins can sign in with
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OidcProviders {
+    pub providers: Vec<String>,
+}
+
+/// Parameters the OIDC provider redirected back with
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct OidcCallbackRequest {
+    #[validate(length(min = 1))]
+    pub code: String,
+
+    #[validate(length(min = 1))]
+    pub state: String,
+}
+
+/// External identity linked to an admin account
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct AdminIdentity {
+    pub id: Uuid,
+    pub provider: String,
+    pub issuer: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
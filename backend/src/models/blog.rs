@@ -1,11 +1,12 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 use validator::Validate;
 
 /// Blog post model
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct BlogPost {
     pub id: Uuid,
     pub slug: String,
@@ -21,7 +22,7 @@ pub struct BlogPost {
 }
 
 /// Create blog post request
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateBlogPost {
     #[validate(length(max = 255))]
     pub slug: Option<String>, // Optional - will be auto-generated from UUID if not provided
@@ -41,7 +42,7 @@ pub struct CreateBlogPost {
 }
 
 /// Update blog post request
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateBlogPost {
     #[validate(length(min = 1, max = 500))]
     pub title: Option<String>,
@@ -58,6 +59,7 @@ pub struct UpdateBlogPost {
 }
 
 /// Blog post preview (without full content)
+#[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct BlogPostPreview {
     pub id: Uuid,
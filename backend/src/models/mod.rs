@@ -1,15 +1,26 @@
+pub mod api_token;
 pub mod app;
+pub mod audit;
 pub mod auth;
 pub mod blog;
 pub mod novel;
 
+pub use api_token::{ApiToken, CreateApiToken, CreatedApiToken, Scope};
 pub use app::{
-    get_all_distribution_channels, get_all_platforms, App, CreateApp, DistributionChannel,
+    get_all_distribution_channels, get_all_platforms, App, ChannelInfo, CreateApp, PlatformInfo,
     UpdateApp,
 };
-pub use auth::{Admin, AdminInfo, Claims, LoginRequest, LoginResponse};
-pub use blog::{BlogPost, BlogPostPreview, CreateBlogPost, UpdateBlogPost};
+pub use audit::{AuditEntry, AuditLog};
+pub use auth::{
+    AcceptInvitation, Admin, AdminIdentity, AdminInfo, AdminSummary, ChangePasswordRequest, Claims,
+    CreateInvitation, CurrentAdmin, InvitationResponse, LoginOutcome, LoginRequest, LoginResponse,
+    MfaChallenge, MfaClaims, MfaLoginRequest, OidcAuthorizeResponse, OidcCallbackRequest,
+    OidcProviders, RecoveryCodesResponse, RefreshRequest, RefreshToken, RevokeAllRequest, Role,
+    SessionInfo, TotpConfirmRequest, TotpDisableRequest, TotpSetupResponse, UpdateRole,
+};
+pub use blog::{BlogPost, CreateBlogPost, UpdateBlogPost};
 pub use novel::{
-    get_all_genres, get_all_novel_types, AddRelatedNovel, ChapterPreview, CreateChapter,
-    CreateNovel, Novel, NovelChapter, NovelWithStats, RelatedNovel, UpdateChapter, UpdateNovel,
+    get_all_genres, get_all_novel_types, AddRelatedNovel, CreateChapter, CreateNovel, GenreInfo,
+    Novel, NovelChapter, NovelList, NovelTypeInfo, NovelWithStats, RelatedNovel, UpdateChapter,
+    UpdateNovel,
 };
@@ -1,24 +1,23 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::i18n::{self, Locale};
+
 /// Novel type enum
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum NovelType {
-    Short,  // 단편
-    Long,   // 장편
+    Short, // 단편
+    Long,  // 장편
+    #[default]
     Series, // 연재물
 }
 
-impl Default for NovelType {
-    fn default() -> Self {
-        NovelType::Series
-    }
-}
-
 impl std::fmt::Display for NovelType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -41,22 +40,18 @@ impl From<String> for NovelType {
 }
 
 /// Relation type between novels
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[allow(dead_code)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum RelationType {
-    Related,      // 연관 작품
+    #[default]
+    Related, // 연관 작품
     Sequel,       // 후속작
     Prequel,      // 전편
     Spinoff,      // 스핀오프
     SameUniverse, // 같은 세계관
 }
 
-impl Default for RelationType {
-    fn default() -> Self {
-        RelationType::Related
-    }
-}
-
 impl std::fmt::Display for RelationType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -82,6 +77,9 @@ impl From<String> for RelationType {
     }
 }
 
+/// Novel type ids, in display order
+pub const NOVEL_TYPES: &[&str] = &["short", "long", "series"];
+
 /// Predefined genres
 pub const GENRES: &[&str] = &[
     "fantasy",       // 판타지
@@ -105,7 +103,7 @@ pub const GENRES: &[&str] = &[
 ];
 
 /// Novel model
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Novel {
     pub id: Uuid,
     pub slug: String,
@@ -121,6 +119,7 @@ pub struct Novel {
 }
 
 /// Novel relation model
+#[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct NovelRelation {
     pub id: Uuid,
@@ -131,7 +130,7 @@ pub struct NovelRelation {
 }
 
 /// Related novel info for API response
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct RelatedNovel {
     pub id: Uuid,
     pub slug: String,
@@ -140,7 +139,7 @@ pub struct RelatedNovel {
 }
 
 /// Novel chapter model
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct NovelChapter {
     pub id: Uuid,
     pub novel_id: Uuid,
@@ -154,7 +153,7 @@ pub struct NovelChapter {
 }
 
 /// Create novel request
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateNovel {
     #[validate(length(min = 1, max = 500))]
     pub title: String,
@@ -169,11 +168,12 @@ pub struct CreateNovel {
     pub status: Option<String>, // defaults to 'draft'
 
     // Ignored: slug is always auto-generated from UUID (not title-based)
+    #[allow(dead_code)]
     pub slug: Option<String>,
 }
 
 /// Update novel request
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateNovel {
     #[validate(length(min = 1, max = 500))]
     pub title: Option<String>,
@@ -189,7 +189,7 @@ pub struct UpdateNovel {
 }
 
 /// Add related novel request
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct AddRelatedNovel {
     #[validate(length(min = 1))]
     pub related_novel_slug: String,
@@ -198,7 +198,7 @@ pub struct AddRelatedNovel {
 }
 
 /// Create chapter request
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct CreateChapter {
     pub chapter_number: i32,
 
@@ -212,7 +212,7 @@ pub struct CreateChapter {
 }
 
 /// Update chapter request
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct UpdateChapter {
     #[validate(length(min = 0, max = 500))]
     pub title: Option<String>,
@@ -220,11 +220,12 @@ pub struct UpdateChapter {
     #[validate(length(min = 1))]
     pub content: Option<String>,
 
+    #[allow(dead_code)]
     pub published_at: Option<DateTime<Utc>>,
 }
 
 /// Novel with chapters count and related novels
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct NovelWithStats {
     #[serde(flatten)]
     pub novel: Novel,
@@ -233,7 +234,15 @@ pub struct NovelWithStats {
     pub related_novels: Option<Vec<RelatedNovel>>,
 }
 
+/// Page of novels, with the total matching the filters
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NovelList {
+    pub novels: Vec<Novel>,
+    pub total: i64,
+}
+
 /// Chapter preview (without full content)
+#[allow(dead_code)]
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ChapterPreview {
     pub id: Uuid,
@@ -246,115 +255,39 @@ pub struct ChapterPreview {
 }
 
 /// Genre info for API
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct GenreInfo {
     pub id: &'static str,
     pub name: &'static str,
 }
 
-/// Get all genres with Korean names
-pub fn get_all_genres() -> Vec<GenreInfo> {
-    vec![
-        GenreInfo {
-            id: "fantasy",
-            name: "판타지",
-        },
-        GenreInfo {
-            id: "romance",
-            name: "로맨스",
-        },
-        GenreInfo {
-            id: "action",
-            name: "액션",
-        },
-        GenreInfo {
-            id: "thriller",
-            name: "스릴러",
-        },
-        GenreInfo {
-            id: "mystery",
-            name: "미스터리",
-        },
-        GenreInfo {
-            id: "sf",
-            name: "SF",
-        },
-        GenreInfo {
-            id: "horror",
-            name: "호러",
-        },
-        GenreInfo {
-            id: "drama",
-            name: "드라마",
-        },
-        GenreInfo {
-            id: "comedy",
-            name: "코미디",
-        },
-        GenreInfo {
-            id: "slice_of_life",
-            name: "일상",
-        },
-        GenreInfo {
-            id: "historical",
-            name: "역사",
-        },
-        GenreInfo {
-            id: "martial_arts",
-            name: "무협",
-        },
-        GenreInfo {
-            id: "game",
-            name: "게임",
-        },
-        GenreInfo {
-            id: "sports",
-            name: "스포츠",
-        },
-        GenreInfo {
-            id: "music",
-            name: "음악",
-        },
-        GenreInfo {
-            id: "psychological",
-            name: "심리",
-        },
-        GenreInfo {
-            id: "supernatural",
-            name: "초자연",
-        },
-        GenreInfo {
-            id: "adventure",
-            name: "모험",
-        },
-    ]
+/// Get all genres with names in the given locale
+pub fn get_all_genres(locale: Locale) -> Vec<GenreInfo> {
+    GENRES
+        .iter()
+        .map(|id| GenreInfo {
+            id,
+            name: i18n::text(locale, &format!("genre.{}", id)),
+        })
+        .collect()
 }
 
 /// Novel type info for API
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct NovelTypeInfo {
     pub id: &'static str,
     pub name: &'static str,
     pub description: &'static str,
 }
 
-/// Get all novel types with Korean names
-pub fn get_all_novel_types() -> Vec<NovelTypeInfo> {
-    vec![
-        NovelTypeInfo {
-            id: "short",
-            name: "단편",
-            description: "한 편으로 완결되는 짧은 이야기",
-        },
-        NovelTypeInfo {
-            id: "long",
-            name: "장편",
-            description: "한 권 분량의 완결된 이야기",
-        },
-        NovelTypeInfo {
-            id: "series",
-            name: "연재물",
-            description: "여러 회차로 연재되는 이야기",
-        },
-    ]
+/// Get all novel types with names in the given locale
+pub fn get_all_novel_types(locale: Locale) -> Vec<NovelTypeInfo> {
+    NOVEL_TYPES
+        .iter()
+        .map(|id| NovelTypeInfo {
+            id,
+            name: i18n::text(locale, &format!("novel_type.{}.name", id)),
+            description: i18n::text(locale, &format!("novel_type.{}.description", id)),
+        })
+        .collect()
 }
@@ -0,0 +1,364 @@
+//! OpenAPI description of the latest API version.
+//!
+//! Each module in `routes::API_MODULES` describes its own handlers; this nests
+//! them at their prefixes, adds the bearer scheme and gives every error
+//! response the problem details body. Health, metrics and the JWKS are for
+//! operators and left out.
+
+use axum::http::StatusCode;
+use utoipa::{
+    openapi::{
+        path::Operation,
+        security::{HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme},
+        Content, Ref, RefOr, Response,
+    },
+    OpenApi,
+};
+
+use crate::{
+    error::{Problem, PROBLEM_CONTENT_TYPE},
+    middleware::api_version::ApiVersion,
+    routes::API_MODULES,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "0010capacity Backend API",
+        description = "Errors are problem details (RFC 7807); branch on their `code`.\n\n\
+                       The unversioned `/api` paths are a deprecated alias of `/api/v1`; \
+                       their responses carry `Deprecation` and `Sunset` headers."
+    ),
+    components(schemas(Problem))
+)]
+struct ApiDoc;
+
+/// The spec served at `/api/openapi.json`
+pub fn spec() -> utoipa::openapi::OpenApi {
+    let mut spec = API_MODULES.iter().fold(ApiDoc::openapi(), |spec, module| {
+        let mut api = (module.openapi)();
+        let tag = module.prefix.rsplit('/').next().unwrap_or_default();
+        for operation in operations(&mut api) {
+            operation.tags = Some(vec![tag.to_string()]);
+        }
+
+        let base = format!("{}{}", ApiVersion::LATEST.prefix(), module.prefix);
+        spec.nest_with_path_composer(base, api, join_path)
+    });
+
+    if let Some(components) = spec.components.as_mut() {
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some(
+                        "Access token from a login, or a personal access token. \
+                         Scopes listed on an operation only apply to personal access tokens.",
+                    ))
+                    .build(),
+            ),
+        );
+    }
+
+    for operation in operations(&mut spec) {
+        complete_responses(operation);
+    }
+
+    // Filled in from Cargo.toml, which has no license
+    spec.info.license = None;
+
+    spec
+}
+
+/// A module's root is served without a trailing slash
+fn join_path(base: &str, path: &str) -> String {
+    match path {
+        "/" => base.to_string(),
+        _ => format!("{}{}", base, path),
+    }
+}
+
+fn operations(spec: &mut utoipa::openapi::OpenApi) -> impl Iterator<Item = &mut Operation> {
+    spec.paths.paths.values_mut().flat_map(|item| {
+        [
+            &mut item.get,
+            &mut item.put,
+            &mut item.post,
+            &mut item.delete,
+            &mut item.patch,
+        ]
+        .into_iter()
+        .flatten()
+    })
+}
+
+/// Give documented errors the problem body, document 401 where credentials
+/// are required, cover anything else with a default problem response, and
+/// describe responses documented by status alone
+fn complete_responses(operation: &mut Operation) {
+    let requires_auth = operation.security.as_ref().is_some_and(|requirements| {
+        !requirements.is_empty()
+            && !requirements
+                .iter()
+                .any(|requirement| *requirement == SecurityRequirement::default())
+    });
+
+    let responses = &mut operation.responses.responses;
+    if requires_auth {
+        responses
+            .entry("401".to_string())
+            .or_insert_with(|| Response::new("Missing, invalid or expired credentials").into());
+    }
+    responses
+        .entry("default".to_string())
+        .or_insert_with(|| Response::new("Unexpected error").into());
+
+    for (status, response) in responses.iter_mut() {
+        let RefOr::T(response) = response else {
+            continue;
+        };
+
+        if response.description.is_empty() {
+            if let Some(reason) = status
+                .parse()
+                .ok()
+                .and_then(|status| StatusCode::from_u16(status).ok())
+                .and_then(|status| status.canonical_reason())
+            {
+                response.description = reason.to_string();
+            }
+        }
+
+        if is_error_status(status) && response.content.is_empty() {
+            response.content.insert(
+                PROBLEM_CONTENT_TYPE.to_string(),
+                Content::new(Some(Ref::from_schema_name("Problem"))),
+            );
+        }
+    }
+}
+
+fn is_error_status(status: &str) -> bool {
+    status == "default" || status.parse::<u16>().is_ok_and(|status| status >= 400)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+        http::Method,
+        Router,
+    };
+    use std::collections::BTreeSet;
+    use tower::Service;
+
+    use crate::{db::AppState, error::ErrorCode, routes::api_router};
+
+    /// The latest API as served, answering unmatched paths like the app does
+    fn served_api() -> Router {
+        Router::new()
+            .nest(ApiVersion::LATEST.prefix(), api_router(ApiVersion::LATEST))
+            .fallback(crate::handler_404)
+            .with_state(AppState::for_tests())
+    }
+
+    /// Paths registered on a router, in OpenAPI form (`{param}` for `:param`).
+    /// axum has no API listing routes, but its `Debug` output has the table as
+    /// `RouteId(n): "/path"`; the callers assert a plausible count in case
+    /// that format changes.
+    fn registered_paths(router: &Router) -> BTreeSet<String> {
+        let debug = format!("{:?}", router);
+        let debug = debug
+            .split_once("fallback_router")
+            .map_or(debug.as_str(), |(routes, _)| routes);
+        let mut paths = BTreeSet::new();
+
+        for (at, _) in debug.match_indices("RouteId(") {
+            let Some(path) = debug[at..]
+                .split_once("): \"")
+                .filter(|(id, _)| id["RouteId(".len()..].parse::<u32>().is_ok())
+                .and_then(|(_, rest)| rest.split('"').next())
+            else {
+                continue;
+            };
+
+            paths.insert(
+                path.split('/')
+                    .map(|segment| match segment.strip_prefix(':') {
+                        Some(name) => format!("{{{}}}", name),
+                        None => segment.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("/"),
+            );
+        }
+
+        paths
+    }
+
+    /// A concrete URI for a spec path
+    fn example_uri(path: &str) -> String {
+        path.split('/')
+            .map(|segment| match segment.starts_with('{') {
+                true => "1",
+                false => segment,
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Methods the router serves at a path, read from the `Allow` header of a
+    /// 405 to a method no route uses
+    async fn served_methods(app: &mut Router, path: &str) -> BTreeSet<String> {
+        let response = app
+            .call(
+                Request::builder()
+                    .method(Method::TRACE)
+                    .uri(example_uri(path))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            response.status(),
+            StatusCode::METHOD_NOT_ALLOWED,
+            "{} is not served",
+            path
+        );
+
+        response.headers()[axum::http::header::ALLOW]
+            .to_str()
+            .unwrap()
+            .split(',')
+            .map(|method| method.trim().to_ascii_lowercase())
+            .filter(|method| method != "head")
+            .collect()
+    }
+
+    fn documented_routes() -> BTreeSet<(String, String)> {
+        spec()
+            .paths
+            .paths
+            .iter()
+            .flat_map(|(path, item)| {
+                [
+                    ("get", item.get.is_some()),
+                    ("put", item.put.is_some()),
+                    ("post", item.post.is_some()),
+                    ("delete", item.delete.is_some()),
+                    ("patch", item.patch.is_some()),
+                ]
+                .into_iter()
+                .filter(|(_, present)| *present)
+                .map(|(method, _)| (method.to_string(), path.clone()))
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn app_router_builds() {
+        // Overlapping routes and nest conflicts panic here rather than at startup
+        let _ = crate::create_router(AppState::for_tests());
+    }
+
+    #[tokio::test]
+    async fn documented_routes_are_served() {
+        let mut app = served_api();
+        let documented = documented_routes();
+        assert!(documented.len() > 40, "found only {}", documented.len());
+
+        // Handlers run and fail without a database; only routing matters here
+        for (method, path) in &documented {
+            let response = app
+                .call(
+                    Request::builder()
+                        .method(method.to_uppercase().as_str())
+                        .uri(example_uri(path))
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            let status = response.status();
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let unrouted = status == StatusCode::METHOD_NOT_ALLOWED
+                || serde_json::from_slice::<serde_json::Value>(&body)
+                    .is_ok_and(|problem| problem["code"] == ErrorCode::RouteNotFound.as_str());
+
+            assert!(
+                !unrouted,
+                "{} {} is documented but not served",
+                method, path
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn served_routes_are_documented() {
+        let mut app = served_api();
+        let paths = registered_paths(&app);
+        assert!(paths.len() > 30, "found only {} paths", paths.len());
+
+        let mut served = BTreeSet::new();
+        for path in paths {
+            for method in served_methods(&mut app, &path).await {
+                served.insert((method, path.clone()));
+            }
+        }
+
+        let missing: Vec<_> = served.difference(&documented_routes()).cloned().collect();
+        assert!(
+            missing.is_empty(),
+            "routes missing from the spec: {:?}",
+            missing
+        );
+    }
+
+    #[test]
+    fn schema_references_resolve() {
+        let spec = serde_json::to_value(spec()).unwrap();
+        let schemas = &spec["components"]["schemas"];
+
+        let mut pending = vec![&spec];
+        while let Some(value) = pending.pop() {
+            match value {
+                serde_json::Value::Object(map) => {
+                    if let Some(serde_json::Value::String(reference)) = map.get("$ref") {
+                        let name = reference
+                            .strip_prefix("#/components/schemas/")
+                            .expect("only schema references");
+                        assert!(schemas.get(name).is_some(), "unresolved {}", reference);
+                    }
+                    pending.extend(map.values());
+                }
+                serde_json::Value::Array(items) => pending.extend(items),
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn errors_are_problems() {
+        let mut spec = spec();
+        for operation in operations(&mut spec) {
+            let responses = &operation.responses.responses;
+            assert!(responses.contains_key("default"));
+
+            for (status, response) in responses {
+                if let (true, RefOr::T(response)) = (is_error_status(status), response) {
+                    assert!(
+                        response.content.contains_key(PROBLEM_CONTENT_TYPE),
+                        "{} response of {:?} has no problem body",
+                        status,
+                        operation.operation_id
+                    );
+                }
+            }
+        }
+    }
+}
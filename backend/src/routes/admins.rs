@@ -0,0 +1,284 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post, put},
+    Router,
+};
+use chrono::{Duration, Utc};
+use sqlx::PgConnection;
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use crate::{
+    auth::tokens::{generate_opaque_token, hash_token},
+    db::{audit::AuditEvent, AppState},
+    error::{AppError, ErrorCode},
+    middleware::{
+        auth::{Owner, RequireRole},
+        extract::{Json, Path},
+        request_meta::RequestMeta,
+    },
+    models::{AdminSummary, CreateInvitation, InvitationResponse, UpdateRole},
+};
+
+/// How long an invitation can be accepted
+const INVITATION_TTL_DAYS: i64 = 7;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_admins))
+        .route("/invitations", post(create_invitation))
+        .route("/:id/role", put(update_role))
+        .route("/:id/disable", post(disable_admin))
+        .route("/:id/enable", post(enable_admin))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_admins,
+    create_invitation,
+    update_role,
+    disable_admin,
+    enable_admin
+))]
+pub struct AdminsApi;
+
+/// List all admin accounts (requires owner role)
+#[utoipa::path(
+    get,
+    path = "/",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, body = Vec<AdminSummary>),
+        (status = 403, description = "Requires the owner role and a login session"),
+    )
+)]
+async fn list_admins(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Owner>,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_session()?;
+
+    let admins = sqlx::query_as::<_, AdminSummary>(
+        "SELECT id, username, role, disabled_at, created_at FROM admins ORDER BY created_at ASC",
+    )
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(admins))
+}
+
+/// Invite a new admin with the given role (requires owner role)
+#[utoipa::path(
+    post,
+    path = "/invitations",
+    request_body = CreateInvitation,
+    security(("bearer" = [])),
+    responses(
+        (status = 201, body = InvitationResponse),
+        (status = 403, description = "Requires the owner role and a login session"),
+    )
+)]
+async fn create_invitation(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Owner>,
+    meta: RequestMeta,
+    Json(payload): Json<CreateInvitation>,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_session()?;
+
+    let token = generate_opaque_token();
+    let expires_at = Utc::now() + Duration::days(INVITATION_TTL_DAYS);
+
+    let id: Uuid = sqlx::query_scalar(
+        "INSERT INTO admin_invitations (token_hash, role, invited_by, expires_at)
+         VALUES ($1, $2, $3, $4)
+         RETURNING id",
+    )
+    .bind(hash_token(&token))
+    .bind(payload.role.to_string())
+    .bind(auth.user_id)
+    .bind(expires_at)
+    .fetch_one(&state.pool)
+    .await?;
+
+    AuditEvent::new(&auth, "create", "invitation", id)
+        .after(&serde_json::json!({
+            "role": payload.role,
+            "expires_at": expires_at,
+        }))
+        .record(&state.pool, &meta)
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(InvitationResponse {
+            id,
+            token,
+            role: payload.role,
+            expires_at,
+        }),
+    ))
+}
+
+/// Change another admin's role (requires owner role)
+#[utoipa::path(
+    put,
+    path = "/{id}/role",
+    params(("id" = Uuid, Path, description = "Admin id")),
+    request_body = UpdateRole,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, body = AdminSummary),
+        (status = 400, description = "Owners cannot change their own role"),
+        (status = 403, description = "Requires the owner role and a login session"),
+        (status = 404, description = "No admin with this id"),
+    )
+)]
+async fn update_role(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Owner>,
+    meta: RequestMeta,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateRole>,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_session()?;
+
+    // Owners can't demote themselves, so there is always at least one owner
+    if id == auth.user_id {
+        return Err(AppError::BadRequest(ErrorCode::CannotChangeOwnRole));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    let before = lock_admin(&mut tx, id).await?;
+
+    let admin = sqlx::query_as::<_, AdminSummary>(
+        "UPDATE admins SET role = $1, updated_at = NOW() WHERE id = $2
+         RETURNING id, username, role, disabled_at, created_at",
+    )
+    .bind(payload.role.to_string())
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    AuditEvent::new(&auth, "update_role", "admin", admin.id)
+        .before(&before)
+        .after(&admin)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
+
+    // Access tokens carry the old role; make the account refresh to pick up the new one
+    state
+        .revocations
+        .expire_access_tokens(admin.id, Utc::now())
+        .await?;
+
+    Ok(Json(admin))
+}
+
+/// Disable another admin and sign them out everywhere (requires owner role)
+#[utoipa::path(
+    post,
+    path = "/{id}/disable",
+    params(("id" = Uuid, Path, description = "Admin id")),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, body = AdminSummary),
+        (status = 400, description = "Owners cannot disable themselves"),
+        (status = 403, description = "Requires the owner role and a login session"),
+        (status = 404, description = "No admin with this id"),
+    )
+)]
+async fn disable_admin(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Owner>,
+    meta: RequestMeta,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_session()?;
+
+    if id == auth.user_id {
+        return Err(AppError::BadRequest(ErrorCode::CannotDisableSelf));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    let before = lock_admin(&mut tx, id).await?;
+
+    let admin = sqlx::query_as::<_, AdminSummary>(
+        "UPDATE admins SET disabled_at = COALESCE(disabled_at, NOW()), updated_at = NOW()
+         WHERE id = $1
+         RETURNING id, username, role, disabled_at, created_at",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    AuditEvent::new(&auth, "disable", "admin", admin.id)
+        .before(&before)
+        .after(&admin)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
+
+    state
+        .revocations
+        .revoke_issued_before(admin.id, Utc::now())
+        .await?;
+
+    Ok(Json(admin))
+}
+
+/// Re-enable a disabled admin (requires owner role)
+#[utoipa::path(
+    post,
+    path = "/{id}/enable",
+    params(("id" = Uuid, Path, description = "Admin id")),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, body = AdminSummary),
+        (status = 403, description = "Requires the owner role and a login session"),
+        (status = 404, description = "No admin with this id"),
+    )
+)]
+async fn enable_admin(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Owner>,
+    meta: RequestMeta,
+    Path(id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_session()?;
+
+    let mut tx = state.pool.begin().await?;
+    let before = lock_admin(&mut tx, id).await?;
+
+    let admin = sqlx::query_as::<_, AdminSummary>(
+        "UPDATE admins SET disabled_at = NULL, updated_at = NOW()
+         WHERE id = $1
+         RETURNING id, username, role, disabled_at, created_at",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    AuditEvent::new(&auth, "enable", "admin", admin.id)
+        .before(&before)
+        .after(&admin)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
+
+    Ok(Json(admin))
+}
+
+/// Load an admin for update, so the audit snapshot matches what gets changed
+async fn lock_admin(conn: &mut PgConnection, id: Uuid) -> Result<AdminSummary, AppError> {
+    sqlx::query_as::<_, AdminSummary>(
+        "SELECT id, username, role, disabled_at, created_at FROM admins WHERE id = $1 FOR UPDATE",
+    )
+    .bind(id)
+    .fetch_optional(conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound(ErrorCode::AdminNotFound))
+}
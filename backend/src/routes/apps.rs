@@ -1,19 +1,22 @@
-use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
-    routing::get,
-    Json, Router,
-};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Router};
 use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi};
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    db::AppState,
-    error::AppError,
-    middleware::auth::AuthUser,
-    models::{get_all_distribution_channels, get_all_platforms, App, CreateApp, UpdateApp},
+    db::{audit::AuditEvent, AppState},
+    error::{AppError, ErrorCode},
+    middleware::{
+        auth::{Editor, RequireRole},
+        extract::{Json, Path, Query},
+        locale::Lang,
+        request_meta::RequestMeta,
+    },
+    models::{
+        get_all_distribution_channels, get_all_platforms, App, ChannelInfo, CreateApp,
+        PlatformInfo, Scope, UpdateApp,
+    },
 };
 
 pub fn router() -> Router<AppState> {
@@ -24,11 +27,25 @@ pub fn router() -> Router<AppState> {
         .route("/:slug", get(get_app).put(update_app).delete(delete_app))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_apps,
+    create_app,
+    list_platforms,
+    list_channels,
+    get_app,
+    update_app,
+    delete_app,
+))]
+pub struct AppsApi;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct ListQuery {
     #[serde(default)]
     platform: Option<String>,
     #[serde(default = "default_limit")]
+    #[param(default = default_limit)]
     limit: i64,
     #[serde(default)]
     offset: i64,
@@ -72,16 +89,34 @@ async fn ensure_unique_slug(pool: &sqlx::PgPool, base_slug: &str) -> Result<Stri
 }
 
 /// List all available platforms
-async fn list_platforms() -> impl IntoResponse {
-    Json(get_all_platforms())
+#[utoipa::path(
+    get,
+    path = "/platforms",
+    params(Lang),
+    responses((status = 200, description = "Platforms with localized names", body = Vec<PlatformInfo>))
+)]
+async fn list_platforms(lang: Lang) -> impl IntoResponse {
+    (lang, Json(get_all_platforms(lang.0)))
 }
 
 /// List all available distribution channels
-async fn list_channels() -> impl IntoResponse {
-    Json(get_all_distribution_channels())
+#[utoipa::path(
+    get,
+    path = "/channels",
+    params(Lang),
+    responses((status = 200, description = "Distribution channels with localized names", body = Vec<ChannelInfo>))
+)]
+async fn list_channels(lang: Lang) -> impl IntoResponse {
+    (lang, Json(get_all_distribution_channels(lang.0)))
 }
 
 /// List all apps
+#[utoipa::path(
+    get,
+    path = "/",
+    params(ListQuery),
+    responses((status = 200, description = "Apps, newest first", body = Vec<App>))
+)]
 async fn list_apps(
     State(state): State<AppState>,
     Query(query): Query<ListQuery>,
@@ -108,6 +143,15 @@ async fn list_apps(
 }
 
 /// Get a single app by slug
+#[utoipa::path(
+    get,
+    path = "/{slug}",
+    params(("slug" = String, Path, description = "App slug")),
+    responses(
+        (status = 200, body = App),
+        (status = 404, description = "No app with this slug"),
+    )
+)]
 async fn get_app(
     State(state): State<AppState>,
     Path(slug): Path<String>,
@@ -116,18 +160,33 @@ async fn get_app(
         "SELECT id, name, slug, description, platforms, screenshots, distribution_channels, privacy_policy_url, created_at, updated_at FROM apps WHERE slug = $1"
     )
     .bind(&slug)
-    .fetch_one(&state.pool)
-    .await?;
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(AppError::NotFound(ErrorCode::AppNotFound))?;
 
     Ok(Json(app))
 }
 
-/// Create a new app (requires authentication)
+/// Create a new app (requires editor role)
+#[utoipa::path(
+    post,
+    path = "/",
+    request_body = CreateApp,
+    security(("bearer" = ["apps:write"])),
+    responses(
+        (status = 201, body = App),
+        (status = 400, description = "Invalid app"),
+        (status = 403, description = "Requires the editor role"),
+    )
+)]
 async fn create_app(
     State(state): State<AppState>,
-    _auth: AuthUser,
+    RequireRole(auth, _): RequireRole<Editor>,
+    meta: RequestMeta,
     Json(payload): Json<CreateApp>,
 ) -> Result<impl IntoResponse, AppError> {
+    auth.require_scope(Scope::AppsWrite)?;
+
     payload.validate()?;
 
     // Always generate UUID-based slug
@@ -141,6 +200,7 @@ async fn create_app(
         None => serde_json::json!([]),
     };
 
+    let mut tx = state.pool.begin().await?;
     let app = sqlx::query_as::<_, App>(
         "INSERT INTO apps (name, slug, description, platforms, screenshots, distribution_channels, privacy_policy_url)
          VALUES ($1, $2, $3, $4, $5, $6, $7)
@@ -153,19 +213,41 @@ async fn create_app(
     .bind(&screenshots)
     .bind(&distribution_channels)
     .bind(&payload.privacy_policy_url)
-    .fetch_one(&state.pool)
+    .fetch_one(&mut *tx)
     .await?;
 
+    AuditEvent::new(&auth, "create", "app", app.id)
+        .after(&app)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
+
     Ok((StatusCode::CREATED, Json(app)))
 }
 
-/// Update an app (requires authentication)
+/// Update an app (requires editor role)
+#[utoipa::path(
+    put,
+    path = "/{slug}",
+    params(("slug" = String, Path, description = "App slug")),
+    request_body = UpdateApp,
+    security(("bearer" = ["apps:write"])),
+    responses(
+        (status = 200, body = App),
+        (status = 400, description = "Invalid or empty update"),
+        (status = 403, description = "Requires the editor role"),
+        (status = 404, description = "No app with this slug"),
+    )
+)]
 async fn update_app(
     State(state): State<AppState>,
-    _auth: AuthUser,
+    RequireRole(auth, _): RequireRole<Editor>,
+    meta: RequestMeta,
     Path(slug): Path<String>,
     Json(payload): Json<UpdateApp>,
 ) -> Result<impl IntoResponse, AppError> {
+    auth.require_scope(Scope::AppsWrite)?;
+
     // Build dynamic update query
     let mut set_clauses = Vec::<String>::new();
     let mut param_idx = 1;
@@ -205,7 +287,7 @@ async fn update_app(
     }
 
     if set_clauses.is_empty() {
-        return Err(AppError::BadRequest("No fields to update".to_string()));
+        return Err(AppError::BadRequest(ErrorCode::NoFieldsToUpdate));
     }
 
     set_clauses.push("updated_at = NOW()".to_string());
@@ -242,21 +324,65 @@ async fn update_app(
     // Bind slug for WHERE clause
     query = query.bind(&slug);
 
-    let app = query.fetch_one(&state.pool).await?;
+    let mut tx = state.pool.begin().await?;
+
+    let before = sqlx::query_as::<_, App>(
+        "SELECT id, name, slug, description, platforms, screenshots, distribution_channels, privacy_policy_url, created_at, updated_at
+         FROM apps WHERE slug = $1 FOR UPDATE",
+    )
+    .bind(&slug)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound(ErrorCode::AppNotFound))?;
+
+    let app = query.fetch_one(&mut *tx).await?;
+
+    AuditEvent::new(&auth, "update", "app", app.id)
+        .before(&before)
+        .after(&app)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
 
     Ok(Json(app))
 }
 
-/// Delete an app (requires authentication)
+/// Delete an app (requires editor role)
+#[utoipa::path(
+    delete,
+    path = "/{slug}",
+    params(("slug" = String, Path, description = "App slug")),
+    security(("bearer" = ["apps:write"])),
+    responses(
+        (status = 204, description = "Deleted, or did not exist"),
+        (status = 403, description = "Requires the editor role"),
+    )
+)]
 async fn delete_app(
     State(state): State<AppState>,
-    _auth: AuthUser,
+    RequireRole(auth, _): RequireRole<Editor>,
+    meta: RequestMeta,
     Path(slug): Path<String>,
 ) -> Result<StatusCode, AppError> {
-    sqlx::query("DELETE FROM apps WHERE slug = $1")
-        .bind(&slug)
-        .execute(&state.pool)
-        .await?;
+    auth.require_scope(Scope::AppsWrite)?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let deleted = sqlx::query_as::<_, App>(
+        "DELETE FROM apps WHERE slug = $1
+         RETURNING id, name, slug, description, platforms, screenshots, distribution_channels, privacy_policy_url, created_at, updated_at",
+    )
+    .bind(&slug)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if let Some(app) = deleted {
+        AuditEvent::new(&auth, "delete", "app", app.id)
+            .before(&app)
+            .record(&mut *tx, &meta)
+            .await?;
+    }
+    tx.commit().await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
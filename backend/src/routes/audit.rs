@@ -0,0 +1,150 @@
+use axum::{extract::State, response::IntoResponse, routing::get, Router};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi};
+use uuid::Uuid;
+
+use crate::{
+    db::AppState,
+    error::AppError,
+    middleware::{
+        auth::{Editor, RequireRole},
+        extract::{Json, Query},
+    },
+    models::{AuditEntry, AuditLog},
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/", get(list_entries))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_entries))]
+pub struct AuditApi;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct AuditQuery {
+    #[serde(default)]
+    entity_type: Option<String>,
+    #[serde(default)]
+    entity_id: Option<String>,
+    #[serde(default)]
+    actor_id: Option<Uuid>,
+    #[serde(default)]
+    action: Option<String>,
+    #[serde(default)]
+    from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    to: Option<DateTime<Utc>>,
+    #[serde(default = "default_limit")]
+    #[param(default = default_limit)]
+    limit: i64,
+    #[serde(default)]
+    offset: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+/// Query the audit log, newest first (requires editor role)
+#[utoipa::path(
+    get,
+    path = "/",
+    params(AuditQuery),
+    security(("bearer" = [])),
+    responses(
+        (status = 200, body = AuditLog),
+        (status = 403, description = "Requires the editor role and a login session"),
+    )
+)]
+async fn list_entries(
+    State(state): State<AppState>,
+    RequireRole(auth, _): RequireRole<Editor>,
+    Query(query): Query<AuditQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_session()?;
+
+    let mut conditions = Vec::new();
+    let mut param_count = 0;
+
+    if query.entity_type.is_some() {
+        param_count += 1;
+        conditions.push(format!("entity_type = ${}", param_count));
+    }
+    if query.entity_id.is_some() {
+        param_count += 1;
+        conditions.push(format!("entity_id = ${}", param_count));
+    }
+    if query.actor_id.is_some() {
+        param_count += 1;
+        conditions.push(format!("actor_id = ${}", param_count));
+    }
+    if query.action.is_some() {
+        param_count += 1;
+        conditions.push(format!("action = ${}", param_count));
+    }
+    if query.from.is_some() {
+        param_count += 1;
+        conditions.push(format!("created_at >= ${}", param_count));
+    }
+    if query.to.is_some() {
+        param_count += 1;
+        conditions.push(format!("created_at < ${}", param_count));
+    }
+
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+
+    let sql = format!(
+        "SELECT id, actor_id, actor_username, action, entity_type, entity_id, before, after, request_id, ip, created_at
+         FROM audit_log{}
+         ORDER BY created_at DESC
+         LIMIT ${} OFFSET ${}",
+        where_clause,
+        param_count + 1,
+        param_count + 2
+    );
+    let count_sql = format!("SELECT COUNT(*) FROM audit_log{}", where_clause);
+
+    let mut entries_query = sqlx::query_as::<_, AuditEntry>(&sql);
+    let mut count_query = sqlx::query_scalar::<_, i64>(&count_sql);
+
+    if let Some(ref entity_type) = query.entity_type {
+        entries_query = entries_query.bind(entity_type);
+        count_query = count_query.bind(entity_type);
+    }
+    if let Some(ref entity_id) = query.entity_id {
+        entries_query = entries_query.bind(entity_id);
+        count_query = count_query.bind(entity_id);
+    }
+    if let Some(actor_id) = query.actor_id {
+        entries_query = entries_query.bind(actor_id);
+        count_query = count_query.bind(actor_id);
+    }
+    if let Some(ref action) = query.action {
+        entries_query = entries_query.bind(action);
+        count_query = count_query.bind(action);
+    }
+    if let Some(from) = query.from {
+        entries_query = entries_query.bind(from);
+        count_query = count_query.bind(from);
+    }
+    if let Some(to) = query.to {
+        entries_query = entries_query.bind(to);
+        count_query = count_query.bind(to);
+    }
+
+    let entries = entries_query
+        .bind(query.limit.clamp(1, 200))
+        .bind(query.offset.max(0))
+        .fetch_all(&state.pool)
+        .await?;
+    let total = count_query.fetch_one(&state.pool).await?;
+
+    Ok(Json(AuditLog { entries, total }))
+}
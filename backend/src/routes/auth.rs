@@ -2,78 +2,699 @@ use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
-use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::post, Json, Router};
-use chrono::{Duration, Utc};
-use jsonwebtoken::{encode, EncodingKey, Header};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{delete, get, post},
+    Router,
+};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use sqlx::{PgConnection, PgPool};
+use std::{net::IpAddr, sync::OnceLock};
+use utoipa::OpenApi;
+use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    db::AppState,
-    error::AppError,
-    models::{Admin, AdminInfo, Claims, LoginRequest, LoginResponse},
+    auth::{
+        keys::JwtKeys,
+        oidc::Identity,
+        tokens::{
+            decode_mfa_token, encode_access_token, encode_mfa_token, generate_opaque_token,
+            hash_token,
+        },
+        totp,
+    },
+    config::Config,
+    db::{audit::AuditEvent, AppState},
+    error::{AppError, ErrorCode},
+    middleware::{
+        auth::{AuthUser, Credential, OptionalAuthUser},
+        client_ip::ClientIp,
+        extract::{Json, Path},
+        request_meta::RequestMeta,
+    },
+    models::{
+        AcceptInvitation, Admin, AdminIdentity, AdminInfo, ChangePasswordRequest, CurrentAdmin,
+        LoginOutcome, LoginRequest, LoginResponse, MfaChallenge, MfaLoginRequest,
+        OidcAuthorizeResponse, OidcCallbackRequest, OidcProviders, RecoveryCodesResponse,
+        RefreshRequest, RefreshToken, RevokeAllRequest, Role, SessionInfo, TotpConfirmRequest,
+        TotpDisableRequest, TotpSetupResponse,
+    },
 };
 
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/login", post(login))
+        .route("/login/mfa", post(login_mfa))
+        .route("/refresh", post(refresh))
+        .route("/logout", post(logout))
+        .route("/revoke-all", post(revoke_all))
+        .route("/me", get(me))
+        .route("/password", post(change_password))
+        .route("/sessions", get(list_sessions))
         .route("/register", post(register_admin))
+        .route("/accept-invite", post(accept_invitation))
+        .route("/2fa/setup", post(setup_totp))
+        .route("/2fa/confirm", post(confirm_totp))
+        .route("/2fa/disable", post(disable_totp))
+        .route("/oidc/providers", get(list_oidc_providers))
+        .route("/oidc/:provider/authorize", post(oidc_authorize))
+        .route("/oidc/:provider/callback", post(oidc_callback))
+        .route("/identities", get(list_identities))
+        .route("/identities/:id", delete(unlink_identity))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    login,
+    login_mfa,
+    refresh,
+    logout,
+    revoke_all,
+    me,
+    change_password,
+    list_sessions,
+    register_admin,
+    accept_invitation,
+    setup_totp,
+    confirm_totp,
+    disable_totp,
+    list_oidc_providers,
+    oidc_authorize,
+    oidc_callback,
+    list_identities,
+    unlink_identity,
+))]
+pub struct AuthApi;
+
+/// How long the browser has to complete sign-in at an OIDC provider
+const OIDC_STATE_TTL_SECONDS: i64 = 600;
+
+/// Issue an access token plus a new refresh token in the given family
+async fn issue_tokens(
+    conn: &mut PgConnection,
+    keys: &JwtKeys,
+    config: &Config,
+    admin: Admin,
+    family_id: Uuid,
+    meta: &RequestMeta,
+) -> Result<LoginResponse, AppError> {
+    let (token, expires_at) = encode_access_token(keys, config, &admin, family_id)?;
+
+    let refresh_token = generate_opaque_token();
+    let refresh_expires_at = Utc::now() + Duration::seconds(config.jwt.refresh_token_expiration);
+
+    sqlx::query(
+        "INSERT INTO refresh_tokens (admin_id, family_id, token_hash, expires_at, user_agent, ip)
+         VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(admin.id)
+    .bind(family_id)
+    .bind(hash_token(&refresh_token))
+    .bind(refresh_expires_at)
+    .bind(&meta.user_agent)
+    .bind(meta.ip.map(|ip| ip.to_string()))
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(LoginResponse {
+        token,
+        expires_at,
+        refresh_token,
+        refresh_expires_at,
+        user: AdminInfo::from(admin),
+    })
+}
+
+/// Revoke every refresh token in a family
+async fn revoke_family(conn: &mut PgConnection, family_id: Uuid) -> Result<(), AppError> {
+    sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = NOW()
+         WHERE family_id = $1 AND revoked_at IS NULL",
+    )
+    .bind(family_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
 }
 
 /// Login handler
+#[utoipa::path(
+    post,
+    path = "/login",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Tokens, or an MFA challenge when 2FA is enabled", body = LoginOutcome),
+        (status = 400, description = "Invalid request"),
+        (status = 401, description = "Wrong username or password"),
+        (status = 403, description = "Account disabled"),
+        (status = 429, description = "Too many failed attempts, see `Retry-After`"),
+    )
+)]
 async fn login(
     State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    meta: RequestMeta,
     Json(payload): Json<LoginRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     payload.validate()?;
 
+    if let Some(wait) = state.login_throttle.retry_after(&payload.username, ip) {
+        record_login_event(&state, &payload.username, None, ip, "throttled").await;
+        return Err(too_many_requests(wait));
+    }
+
     // Find admin by username
     let admin = sqlx::query_as::<_, Admin>(
-        "SELECT id, username, password_hash, created_at FROM admins WHERE username = $1",
+        "SELECT id, username, password_hash, role, disabled_at, totp_enabled_at, locked_until, created_at FROM admins WHERE username = $1",
     )
     .bind(&payload.username)
     .fetch_optional(&state.pool)
+    .await?;
+
+    // Always run Argon2, against a dummy hash for unknown usernames, so response
+    // timing doesn't reveal which accounts exist
+    let password_ok = match &admin {
+        Some(admin) => verify_password(&admin.password_hash, &payload.password).is_ok(),
+        None => {
+            let _ = verify_password(dummy_password_hash(), &payload.password);
+            false
+        }
+    };
+
+    if let Some(locked_for) = admin.as_ref().and_then(Admin::locked_for) {
+        let admin_id = admin.as_ref().map(|admin| admin.id);
+        record_login_event(&state, &payload.username, admin_id, ip, "locked").await;
+        return Err(too_many_requests(locked_for.to_std().unwrap_or_default()));
+    }
+
+    let admin = match admin {
+        Some(admin) if password_ok => admin,
+        admin => {
+            record_login_failure(&state, &payload.username, admin.as_ref(), ip).await?;
+            record_login_event(
+                &state,
+                &payload.username,
+                admin.map(|admin| admin.id),
+                ip,
+                "invalid_credentials",
+            )
+            .await;
+            return Err(AppError::InvalidCredentials);
+        }
+    };
+
+    if admin.is_disabled() {
+        record_login_event(&state, &admin.username, Some(admin.id), ip, "disabled").await;
+        return Err(AppError::Forbidden(ErrorCode::AccountDisabled));
+    }
+
+    // With 2FA enabled, the password alone only buys a short-lived mfa token.
+    // Failure counters are only reset once the second step succeeds too.
+    if admin.has_totp() {
+        let (mfa_token, expires_at) = encode_mfa_token(&state.jwt_keys, admin.id)?;
+        record_login_event(&state, &admin.username, Some(admin.id), ip, "mfa_required").await;
+
+        return Ok(Json(LoginOutcome::MfaRequired(MfaChallenge {
+            mfa_required: true,
+            mfa_token,
+            expires_at,
+        })));
+    }
+
+    reset_login_failures(&state, &admin).await?;
+    record_login_event(&state, &admin.username, Some(admin.id), ip, "success").await;
+
+    // Every login starts a new refresh token family
+    let mut conn = state.pool.acquire().await?;
+    let response = issue_tokens(
+        &mut conn,
+        &state.jwt_keys,
+        &state.config,
+        admin,
+        Uuid::new_v4(),
+        &meta,
+    )
+    .await?;
+
+    Ok(Json(LoginOutcome::Authenticated(response)))
+}
+
+/// Second login step for admins with 2FA: exchange the mfa token and a code
+#[utoipa::path(
+    post,
+    path = "/login/mfa",
+    request_body = MfaLoginRequest,
+    responses(
+        (status = 200, body = LoginResponse),
+        (status = 400, description = "Invalid request, or no code given"),
+        (status = 401, description = "Invalid or expired mfa token, or wrong code"),
+        (status = 403, description = "Account disabled"),
+        (status = 429, description = "Too many failed attempts, see `Retry-After`"),
+    )
+)]
+async fn login_mfa(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    meta: RequestMeta,
+    Json(payload): Json<MfaLoginRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let claims = decode_mfa_token(&state.jwt_keys, &payload.mfa_token)?;
+    let admin_id = Uuid::parse_str(&claims.sub).map_err(|_| AppError::InvalidToken)?;
+    let jti = Uuid::parse_str(&claims.jti).map_err(|_| AppError::InvalidToken)?;
+
+    if state
+        .revocations
+        .is_revoked(jti, admin_id, claims.iat)
+        .await
+    {
+        return Err(AppError::InvalidToken);
+    }
+
+    let admin = sqlx::query_as::<_, Admin>(
+        "SELECT id, username, password_hash, role, disabled_at, totp_enabled_at, locked_until, created_at FROM admins WHERE id = $1",
+    )
+    .bind(admin_id)
+    .fetch_optional(&state.pool)
     .await?
-    .ok_or(AppError::InvalidCredentials)?;
+    .ok_or(AppError::InvalidToken)?;
 
-    // Verify password
-    let parsed_hash = PasswordHash::new(&admin.password_hash)
-        .map_err(|_| AppError::InternalError("Invalid password hash".to_string()))?;
+    if admin.is_disabled() {
+        return Err(AppError::Forbidden(ErrorCode::AccountDisabled));
+    }
 
-    Argon2::default()
-        .verify_password(payload.password.as_bytes(), &parsed_hash)
-        .map_err(|_| AppError::InvalidCredentials)?;
+    // Codes are only six digits, so guessing them is throttled like passwords
+    if let Some(wait) = state.login_throttle.retry_after(&admin.username, ip) {
+        record_login_event(&state, &admin.username, Some(admin.id), ip, "throttled").await;
+        return Err(too_many_requests(wait));
+    }
 
-    // Generate JWT token
-    let claims = Claims::new(
+    if let Some(locked_for) = admin.locked_for() {
+        record_login_event(&state, &admin.username, Some(admin.id), ip, "locked").await;
+        return Err(too_many_requests(locked_for.to_std().unwrap_or_default()));
+    }
+
+    match verify_second_factor(
+        &state.pool,
         admin.id,
-        admin.username.clone(),
-        state.config.jwt_expiration,
-    );
+        payload.code.as_deref(),
+        payload.recovery_code.as_deref(),
+    )
+    .await
+    {
+        Ok(()) => {}
+        Err(AppError::InvalidCredentials) => {
+            record_login_failure(&state, &admin.username, Some(&admin), ip).await?;
+            record_login_event(&state, &admin.username, Some(admin.id), ip, "mfa_failed").await;
+            return Err(AppError::InvalidCredentials);
+        }
+        Err(e) => return Err(e),
+    }
+
+    reset_login_failures(&state, &admin).await?;
+    record_login_event(&state, &admin.username, Some(admin.id), ip, "success").await;
 
-    let token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(state.config.jwt_secret.as_ref()),
+    // The mfa token is single use
+    let mfa_expires_at = Utc
+        .timestamp_opt(claims.exp, 0)
+        .single()
+        .ok_or(AppError::InvalidToken)?;
+    state
+        .revocations
+        .revoke(jti, admin.id, mfa_expires_at)
+        .await?;
+
+    let mut conn = state.pool.acquire().await?;
+    let response = issue_tokens(
+        &mut conn,
+        &state.jwt_keys,
+        &state.config,
+        admin,
+        Uuid::new_v4(),
+        &meta,
     )
-    .map_err(|_| AppError::InternalError("Token generation failed".to_string()))?;
+    .await?;
 
-    let expires_at = Utc::now() + Duration::seconds(state.config.jwt_expiration);
+    Ok(Json(response))
+}
 
-    Ok(Json(LoginResponse {
-        token,
-        expires_at,
-        user: AdminInfo {
-            id: admin.id,
-            username: admin.username,
-        },
+/// Exchange a refresh token for a new access token, rotating the refresh token
+#[utoipa::path(
+    post,
+    path = "/refresh",
+    request_body = RefreshRequest,
+    responses(
+        (status = 200, body = LoginResponse),
+        (status = 401, description = "Unknown, reused or expired refresh token"),
+        (status = 403, description = "Account disabled"),
+    )
+)]
+async fn refresh(
+    State(state): State<AppState>,
+    meta: RequestMeta,
+    Json(payload): Json<RefreshRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let mut tx = state.pool.begin().await?;
+
+    // Lock the row so concurrent refreshes with the same token are serialized
+    let stored = sqlx::query_as::<_, RefreshToken>(
+        "SELECT id, admin_id, family_id, expires_at, used_at, revoked_at
+         FROM refresh_tokens WHERE token_hash = $1
+         FOR UPDATE",
+    )
+    .bind(hash_token(&payload.refresh_token))
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::InvalidToken)?;
+
+    // A token that was already rotated is being replayed: assume it leaked and
+    // kill the whole chain, including the legitimate holder's current token.
+    if stored.used_at.is_some() || stored.revoked_at.is_some() {
+        if stored.revoked_at.is_none() {
+            tracing::warn!(
+                "Refresh token reuse detected for admin {}, revoking family {}",
+                stored.admin_id,
+                stored.family_id
+            );
+        }
+        revoke_family(&mut tx, stored.family_id).await?;
+        tx.commit().await?;
+        return Err(AppError::InvalidToken);
+    }
+
+    if stored.expires_at <= Utc::now() {
+        return Err(AppError::TokenExpired);
+    }
+
+    sqlx::query("UPDATE refresh_tokens SET used_at = NOW() WHERE id = $1")
+        .bind(stored.id)
+        .execute(&mut *tx)
+        .await?;
+
+    let admin = sqlx::query_as::<_, Admin>(
+        "SELECT id, username, password_hash, role, disabled_at, totp_enabled_at, locked_until, created_at FROM admins WHERE id = $1",
+    )
+    .bind(stored.admin_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::InvalidToken)?;
+
+    if admin.is_disabled() {
+        return Err(AppError::Forbidden(ErrorCode::AccountDisabled));
+    }
+
+    let response = issue_tokens(
+        &mut tx,
+        &state.jwt_keys,
+        &state.config,
+        admin,
+        stored.family_id,
+        &meta,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(Json(response))
+}
+
+/// Logout: revoke the refresh token family the given token belongs to, and the
+/// access token used to call this endpoint if one was sent
+#[utoipa::path(
+    post,
+    path = "/logout",
+    request_body = RefreshRequest,
+    security((), ("bearer" = [])),
+    responses((status = 204, description = "Signed out"))
+)]
+async fn logout(
+    State(state): State<AppState>,
+    OptionalAuthUser(auth): OptionalAuthUser,
+    Json(payload): Json<RefreshRequest>,
+) -> Result<StatusCode, AppError> {
+    payload.validate()?;
+
+    let family_id: Option<Uuid> =
+        sqlx::query_scalar("SELECT family_id FROM refresh_tokens WHERE token_hash = $1")
+            .bind(hash_token(&payload.refresh_token))
+            .fetch_optional(&state.pool)
+            .await?;
+
+    // Unknown tokens are treated as already logged out
+    if let Some(family_id) = family_id {
+        let mut conn = state.pool.acquire().await?;
+        revoke_family(&mut conn, family_id).await?;
+    }
+
+    if let Some(AuthUser {
+        user_id,
+        credential:
+            Credential::Session {
+                token_id,
+                expires_at,
+                ..
+            },
+        ..
+    }) = auth
+    {
+        state
+            .revocations
+            .revoke(token_id, user_id, expires_at)
+            .await?;
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Sign out everywhere: revoke all of the caller's tokens issued before the given time
+#[utoipa::path(
+    post,
+    path = "/revoke-all",
+    request_body = RevokeAllRequest,
+    security(("bearer" = [])),
+    responses(
+        (status = 204, description = "Tokens revoked"),
+        (status = 400, description = "`before` is in the future"),
+        (status = 403, description = "Requires a login session, not an API token"),
+    )
+)]
+async fn revoke_all(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    meta: RequestMeta,
+    Json(payload): Json<RevokeAllRequest>,
+) -> Result<StatusCode, AppError> {
+    auth.require_session()?;
+
+    let now = Utc::now();
+    let before = payload.before.unwrap_or(now);
+
+    if before > now {
+        return Err(AppError::BadRequest(ErrorCode::RevokeInFuture));
+    }
+
+    state
+        .revocations
+        .revoke_issued_before(auth.user_id, before)
+        .await?;
+
+    AuditEvent::new(&auth, "revoke_sessions", "admin", auth.user_id)
+        .after(&serde_json::json!({ "before": before }))
+        .record(&state.pool, &meta)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The caller's account as currently stored, plus how the request was authenticated
+#[utoipa::path(
+    get,
+    path = "/me",
+    security(("bearer" = [])),
+    responses((status = 200, body = CurrentAdmin))
+)]
+async fn me(State(state): State<AppState>, auth: AuthUser) -> Result<impl IntoResponse, AppError> {
+    let admin = sqlx::query_as::<_, Admin>(
+        "SELECT id, username, password_hash, role, disabled_at, totp_enabled_at, locked_until, created_at FROM admins WHERE id = $1",
+    )
+    .bind(auth.user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound(ErrorCode::AdminNotFound))?;
+
+    let (auth_method, scopes) = match auth.credential {
+        Credential::Session { .. } => ("session", None),
+        Credential::ApiToken { scopes, .. } => ("api_token", Some(scopes)),
+    };
+
+    Ok(Json(CurrentAdmin {
+        totp_enabled: admin.has_totp(),
+        created_at: admin.created_at,
+        admin: AdminInfo::from(admin),
+        auth_method,
+        scopes,
     }))
 }
 
+/// Change the caller's password. Every session is signed out, and the caller
+/// gets tokens for a fresh one in the response.
+#[utoipa::path(
+    post,
+    path = "/password",
+    request_body = ChangePasswordRequest,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, body = LoginResponse),
+        (status = 400, description = "Invalid request, or the password is unchanged"),
+        (status = 401, description = "Wrong current password"),
+        (status = 403, description = "Requires a login session, not an API token"),
+        (status = 429, description = "Too many failed attempts, see `Retry-After`"),
+    )
+)]
+async fn change_password(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    meta: RequestMeta,
+    Json(payload): Json<ChangePasswordRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_session()?;
+    payload.validate()?;
+
+    // A stolen access token must not allow guessing the password faster than login does
+    if let Some(wait) = state.login_throttle.retry_after(&auth.username, meta.ip) {
+        return Err(too_many_requests(wait));
+    }
+
+    let admin = sqlx::query_as::<_, Admin>(
+        "SELECT id, username, password_hash, role, disabled_at, totp_enabled_at, locked_until, created_at FROM admins WHERE id = $1",
+    )
+    .bind(auth.user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(AppError::InvalidToken)?;
+
+    if let Some(locked_for) = admin.locked_for() {
+        return Err(too_many_requests(locked_for.to_std().unwrap_or_default()));
+    }
+
+    if verify_password(&admin.password_hash, &payload.current_password).is_err() {
+        record_login_failure(&state, &admin.username, Some(&admin), meta.ip).await?;
+        return Err(AppError::InvalidCredentials);
+    }
+
+    if payload.new_password == payload.current_password {
+        return Err(AppError::BadRequest(ErrorCode::PasswordUnchanged));
+    }
+
+    let password_hash = hash_password(&payload.new_password)?;
+
+    let mut tx = state.pool.begin().await?;
+
+    sqlx::query("UPDATE admins SET password_hash = $2, updated_at = NOW() WHERE id = $1")
+        .bind(admin.id)
+        .bind(&password_hash)
+        .execute(&mut *tx)
+        .await?;
+
+    AuditEvent::new(&auth, "change_password", "admin", admin.id)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
+
+    reset_login_failures(&state, &admin).await?;
+
+    state
+        .revocations
+        .revoke_issued_before(admin.id, Utc::now())
+        .await?;
+
+    let mut conn = state.pool.acquire().await?;
+    let response = issue_tokens(
+        &mut conn,
+        &state.jwt_keys,
+        &state.config,
+        admin,
+        Uuid::new_v4(),
+        &meta,
+    )
+    .await?;
+
+    Ok(Json(response))
+}
+
+/// List the caller's active logins, most recently seen first. A session is
+/// seen whenever it logs in or refreshes its access token.
+#[utoipa::path(
+    get,
+    path = "/sessions",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, body = Vec<SessionInfo>),
+        (status = 403, description = "Requires a login session, not an API token"),
+    )
+)]
+async fn list_sessions(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_session()?;
+
+    let current_session = match auth.credential {
+        Credential::Session { session_id, .. } => session_id,
+        Credential::ApiToken { .. } => None,
+    };
+
+    // The newest token of each family carries the latest client details
+    let mut sessions = sqlx::query_as::<_, SessionInfo>(
+        "SELECT family_id AS id,
+                (array_agg(user_agent ORDER BY created_at DESC))[1] AS user_agent,
+                (array_agg(ip ORDER BY created_at DESC))[1] AS ip,
+                MAX(created_at) AS last_seen_at,
+                MAX(expires_at) AS expires_at,
+                MIN(created_at) AS created_at
+         FROM refresh_tokens
+         WHERE admin_id = $1
+         GROUP BY family_id
+         HAVING bool_or(used_at IS NULL AND revoked_at IS NULL AND expires_at > NOW())
+         ORDER BY last_seen_at DESC",
+    )
+    .bind(auth.user_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    for session in &mut sessions {
+        session.current = current_session == Some(session.id);
+    }
+
+    Ok(Json(sessions))
+}
+
 /// Register admin (should only work if no admin exists)
+#[utoipa::path(
+    post,
+    path = "/register",
+    request_body = LoginRequest,
+    responses(
+        (status = 201, description = "The first admin, as owner", body = Admin),
+        (status = 400, description = "Invalid request"),
+        (status = 403, description = "Registration is disabled"),
+        (status = 409, description = "An admin already exists"),
+    )
+)]
 async fn register_admin(
     State(state): State<AppState>,
     Json(payload): Json<LoginRequest>,
 ) -> Result<impl IntoResponse, AppError> {
+    if !state.config.features.registration {
+        return Err(AppError::Forbidden(ErrorCode::RegistrationDisabled));
+    }
+
     payload.validate()?;
 
     // Check if admin already exists
@@ -82,21 +703,14 @@ async fn register_admin(
         .await?;
 
     if existing.is_some() {
-        return Err(AppError::Conflict(
-            "Admin already exists. Registration is disabled.".to_string(),
-        ));
+        return Err(AppError::Conflict(ErrorCode::AlreadyRegistered));
     }
 
-    // Hash password
-    let salt = SaltString::generate(OsRng);
-    let password_hash = Argon2::default()
-        .hash_password(payload.password.as_bytes(), &salt)
-        .map_err(|_| AppError::InternalError("Password hashing failed".to_string()))?
-        .to_string();
+    let password_hash = hash_password(&payload.password)?;
 
-    // Create admin
+    // The first admin owns the site
     let admin = sqlx::query_as::<_, Admin>(
-        "INSERT INTO admins (username, password_hash) VALUES ($1, $2) RETURNING id, username, password_hash, created_at"
+        "INSERT INTO admins (username, password_hash, role) VALUES ($1, $2, 'owner') RETURNING id, username, password_hash, role, disabled_at, totp_enabled_at, locked_until, created_at"
     )
     .bind(&payload.username)
     .bind(&password_hash)
@@ -105,3 +719,897 @@ async fn register_admin(
 
     Ok((StatusCode::CREATED, Json(admin)))
 }
+
+/// Accept an invitation created by an owner, creating the invited account
+#[utoipa::path(
+    post,
+    path = "/accept-invite",
+    request_body = AcceptInvitation,
+    responses(
+        (status = 201, body = AdminInfo),
+        (status = 400, description = "Invalid request"),
+        (status = 401, description = "Unknown, used or expired invitation"),
+        (status = 409, description = "Username taken"),
+    )
+)]
+async fn accept_invitation(
+    State(state): State<AppState>,
+    Json(payload): Json<AcceptInvitation>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    let mut tx = state.pool.begin().await?;
+
+    // Claim the invitation atomically so it can only be used once
+    let role: Role = sqlx::query_scalar(
+        "UPDATE admin_invitations SET accepted_at = NOW()
+         WHERE token_hash = $1 AND accepted_at IS NULL AND expires_at > NOW()
+         RETURNING role",
+    )
+    .bind(hash_token(&payload.token))
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::InvalidToken)?;
+
+    let password_hash = hash_password(&payload.password)?;
+
+    let admin = sqlx::query_as::<_, Admin>(
+        "INSERT INTO admins (username, password_hash, role) VALUES ($1, $2, $3)
+         RETURNING id, username, password_hash, role, disabled_at, totp_enabled_at, locked_until, created_at",
+    )
+    .bind(&payload.username)
+    .bind(&password_hash)
+    .bind(role)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    sqlx::query("UPDATE admin_invitations SET accepted_by = $1 WHERE token_hash = $2")
+        .bind(admin.id)
+        .bind(hash_token(&payload.token))
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(AdminInfo::from(admin))))
+}
+
+/// Hash a password with Argon2 and a random salt
+pub(crate) fn hash_password(password: &str) -> Result<String, AppError> {
+    let salt = SaltString::generate(OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|_| AppError::InternalError("Password hashing failed".to_string()))
+}
+
+/// Start 2FA enrollment: generate a secret that still has to be confirmed
+#[utoipa::path(
+    post,
+    path = "/2fa/setup",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, body = TotpSetupResponse),
+        (status = 403, description = "Requires a login session, not an API token"),
+        (status = 409, description = "2FA is already enabled"),
+        (status = 429, description = "Too many failed attempts, see `Retry-After`"),
+    )
+)]
+async fn setup_totp(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_session()?;
+
+    let secret = totp::generate_secret();
+
+    let updated = sqlx::query(
+        "UPDATE admins SET totp_secret = $1, updated_at = NOW()
+         WHERE id = $2 AND totp_enabled_at IS NULL",
+    )
+    .bind(&secret)
+    .bind(auth.user_id)
+    .execute(&state.pool)
+    .await?;
+
+    if updated.rows_affected() == 0 {
+        return Err(AppError::Conflict(ErrorCode::TotpAlreadyEnabled));
+    }
+
+    Ok(Json(TotpSetupResponse {
+        provisioning_uri: totp::provisioning_uri(&secret, &auth.username),
+        secret,
+    }))
+}
+
+/// Finish 2FA enrollment with a code from the authenticator app
+#[utoipa::path(
+    post,
+    path = "/2fa/confirm",
+    request_body = TotpConfirmRequest,
+    security(("bearer" = [])),
+    responses(
+        (status = 200, body = RecoveryCodesResponse),
+        (status = 400, description = "Invalid code, or setup not started"),
+        (status = 401, description = "Wrong code"),
+        (status = 403, description = "Requires a login session, not an API token"),
+        (status = 409, description = "2FA is already enabled"),
+    )
+)]
+async fn confirm_totp(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    meta: RequestMeta,
+    Json(payload): Json<TotpConfirmRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_session()?;
+    payload.validate()?;
+
+    // Codes are only six digits, so guessing them is throttled like passwords
+    if let Some(wait) = state.login_throttle.retry_after(&auth.username, meta.ip) {
+        return Err(too_many_requests(wait));
+    }
+
+    let admin = sqlx::query_as::<_, Admin>(
+        "SELECT id, username, password_hash, role, disabled_at, totp_enabled_at, locked_until, created_at FROM admins WHERE id = $1",
+    )
+    .bind(auth.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if admin.has_totp() {
+        return Err(AppError::Conflict(ErrorCode::TotpAlreadyEnabled));
+    }
+
+    if let Some(locked_for) = admin.locked_for() {
+        return Err(too_many_requests(locked_for.to_std().unwrap_or_default()));
+    }
+
+    let secret: Option<String> = sqlx::query_scalar("SELECT totp_secret FROM admins WHERE id = $1")
+        .bind(admin.id)
+        .fetch_one(&state.pool)
+        .await?;
+    let secret = secret.ok_or_else(|| AppError::BadRequest(ErrorCode::TotpSetupNotStarted))?;
+
+    let Some(step) = totp::verify(&secret, &payload.code, Utc::now().timestamp(), None) else {
+        record_login_failure(&state, &admin.username, Some(&admin), meta.ip).await?;
+        return Err(AppError::InvalidCredentials);
+    };
+
+    let recovery_codes: Vec<String> = (0..totp::RECOVERY_CODE_COUNT)
+        .map(|_| totp::generate_recovery_code())
+        .collect();
+
+    let mut tx = state.pool.begin().await?;
+
+    sqlx::query(
+        "UPDATE admins SET totp_enabled_at = NOW(), totp_last_used_step = $1, updated_at = NOW()
+         WHERE id = $2",
+    )
+    .bind(step)
+    .bind(auth.user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM admin_recovery_codes WHERE admin_id = $1")
+        .bind(auth.user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for code in &recovery_codes {
+        sqlx::query("INSERT INTO admin_recovery_codes (admin_id, code_hash) VALUES ($1, $2)")
+            .bind(auth.user_id)
+            .bind(hash_token(&totp::normalize_recovery_code(code)))
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    AuditEvent::new(&auth, "enable_totp", "admin", auth.user_id)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
+
+    reset_login_failures(&state, &admin).await?;
+
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+/// Turn 2FA off; requires the password and a current code or recovery code
+#[utoipa::path(
+    post,
+    path = "/2fa/disable",
+    request_body = TotpDisableRequest,
+    security(("bearer" = [])),
+    responses(
+        (status = 204, description = "2FA disabled"),
+        (status = 400, description = "2FA is not enabled, or no code given"),
+        (status = 401, description = "Wrong password or code"),
+        (status = 403, description = "Requires a login session, not an API token"),
+        (status = 429, description = "Too many failed attempts, see `Retry-After`"),
+    )
+)]
+async fn disable_totp(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    meta: RequestMeta,
+    Json(payload): Json<TotpDisableRequest>,
+) -> Result<StatusCode, AppError> {
+    auth.require_session()?;
+    payload.validate()?;
+
+    // A stolen access token must not allow guessing the password or codes faster than login does
+    if let Some(wait) = state.login_throttle.retry_after(&auth.username, meta.ip) {
+        return Err(too_many_requests(wait));
+    }
+
+    let admin = sqlx::query_as::<_, Admin>(
+        "SELECT id, username, password_hash, role, disabled_at, totp_enabled_at, locked_until, created_at FROM admins WHERE id = $1",
+    )
+    .bind(auth.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if !admin.has_totp() {
+        return Err(AppError::BadRequest(ErrorCode::TotpNotEnabled));
+    }
+
+    if let Some(locked_for) = admin.locked_for() {
+        return Err(too_many_requests(locked_for.to_std().unwrap_or_default()));
+    }
+
+    if verify_password(&admin.password_hash, &payload.password).is_err() {
+        record_login_failure(&state, &admin.username, Some(&admin), meta.ip).await?;
+        return Err(AppError::InvalidCredentials);
+    }
+
+    match verify_second_factor(
+        &state.pool,
+        admin.id,
+        payload.code.as_deref(),
+        payload.recovery_code.as_deref(),
+    )
+    .await
+    {
+        Ok(()) => {}
+        Err(AppError::InvalidCredentials) => {
+            record_login_failure(&state, &admin.username, Some(&admin), meta.ip).await?;
+            return Err(AppError::InvalidCredentials);
+        }
+        Err(e) => return Err(e),
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    sqlx::query(
+        "UPDATE admins
+         SET totp_secret = NULL, totp_enabled_at = NULL, totp_last_used_step = NULL, updated_at = NOW()
+         WHERE id = $1",
+    )
+    .bind(admin.id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM admin_recovery_codes WHERE admin_id = $1")
+        .bind(admin.id)
+        .execute(&mut *tx)
+        .await?;
+
+    AuditEvent::new(&auth, "disable_totp", "admin", admin.id)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
+
+    reset_login_failures(&state, &admin).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// List the OIDC providers admins can sign in with
+#[utoipa::path(
+    get,
+    path = "/oidc/providers",
+    responses((status = 200, body = OidcProviders))
+)]
+async fn list_oidc_providers(State(state): State<AppState>) -> impl IntoResponse {
+    Json(OidcProviders {
+        providers: state
+            .oidc
+            .provider_names()
+            .into_iter()
+            .map(String::from)
+            .collect(),
+    })
+}
+
+/// Start signing in with an OIDC provider. When called with a session, the
+/// identity is linked to the caller's account on callback instead.
+#[utoipa::path(
+    post,
+    path = "/oidc/{provider}/authorize",
+    params(("provider" = String, Path, description = "OIDC provider name")),
+    security((), ("bearer" = [])),
+    responses(
+        (status = 200, body = OidcAuthorizeResponse),
+        (status = 403, description = "Linking requires a login session, not an API token"),
+        (status = 404, description = "No such provider"),
+    )
+)]
+async fn oidc_authorize(
+    State(state): State<AppState>,
+    OptionalAuthUser(auth): OptionalAuthUser,
+    Path(provider): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let link_admin_id = match auth {
+        Some(auth) => {
+            auth.require_session()?;
+            Some(auth.user_id)
+        }
+        None => None,
+    };
+
+    let request = state.oidc.authorization_request(&provider).await?;
+    let expires_at = Utc::now() + Duration::seconds(OIDC_STATE_TTL_SECONDS);
+
+    sqlx::query("DELETE FROM oidc_login_states WHERE expires_at <= NOW()")
+        .execute(&state.pool)
+        .await?;
+
+    sqlx::query(
+        "INSERT INTO oidc_login_states (state_hash, provider, code_verifier, nonce, link_admin_id, expires_at)
+         VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(hash_token(&request.state))
+    .bind(&provider)
+    .bind(&request.code_verifier)
+    .bind(&request.nonce)
+    .bind(link_admin_id)
+    .bind(expires_at)
+    .execute(&state.pool)
+    .await?;
+
+    Ok(Json(OidcAuthorizeResponse {
+        authorization_url: request.url,
+        expires_at,
+    }))
+}
+
+/// Finish signing in with an OIDC provider; responds like a password login
+#[utoipa::path(
+    post,
+    path = "/oidc/{provider}/callback",
+    params(("provider" = String, Path, description = "OIDC provider name")),
+    request_body = OidcCallbackRequest,
+    responses(
+        (status = 200, description = "Tokens, or an MFA challenge when 2FA is enabled", body = LoginOutcome),
+        (status = 400, description = "Invalid request"),
+        (status = 401, description = "Unknown or expired state, or the provider rejected the code"),
+        (status = 403, description = "Identity not linked to an admin, or account disabled"),
+        (status = 404, description = "No such provider"),
+        (status = 409, description = "Identity is linked to another admin"),
+    )
+)]
+async fn oidc_callback(
+    State(state): State<AppState>,
+    ClientIp(ip): ClientIp,
+    meta: RequestMeta,
+    Path(provider): Path<String>,
+    Json(payload): Json<OidcCallbackRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    payload.validate()?;
+
+    // States are single use
+    let (code_verifier, nonce, link_admin_id): (String, String, Option<Uuid>) = sqlx::query_as(
+        "DELETE FROM oidc_login_states
+         WHERE state_hash = $1 AND provider = $2 AND expires_at > NOW()
+         RETURNING code_verifier, nonce, link_admin_id",
+    )
+    .bind(hash_token(&payload.state))
+    .bind(&provider)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(AppError::InvalidToken)?;
+
+    let identity = state
+        .oidc
+        .authenticate(&provider, &payload.code, &code_verifier, &nonce)
+        .await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    if let Some(admin_id) = link_admin_id {
+        link_identity(&mut tx, &meta, admin_id, &provider, &identity).await?;
+    }
+
+    let admin = sqlx::query_as::<_, Admin>(
+        "SELECT a.id, a.username, a.password_hash, a.role, a.disabled_at, a.totp_enabled_at, a.locked_until, a.created_at
+         FROM admins a
+         JOIN admin_identities i ON i.admin_id = a.id
+         WHERE i.issuer = $1 AND i.subject = $2",
+    )
+    .bind(&identity.issuer)
+    .bind(&identity.subject)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(admin) = admin else {
+        let username = identity.email.as_deref().unwrap_or(&identity.subject);
+        record_login_event(&state, username, None, ip, "oidc_unlinked").await;
+        return Err(AppError::Forbidden(ErrorCode::IdentityNotLinked));
+    };
+
+    if admin.is_disabled() {
+        record_login_event(&state, &admin.username, Some(admin.id), ip, "disabled").await;
+        return Err(AppError::Forbidden(ErrorCode::AccountDisabled));
+    }
+
+    sqlx::query(
+        "UPDATE admin_identities SET last_login_at = NOW(), email = COALESCE($3, email)
+         WHERE issuer = $1 AND subject = $2",
+    )
+    .bind(&identity.issuer)
+    .bind(&identity.subject)
+    .bind(&identity.email)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    // The provider replaces the password step, not the second factor
+    if admin.has_totp() {
+        let (mfa_token, expires_at) = encode_mfa_token(&state.jwt_keys, admin.id)?;
+        record_login_event(&state, &admin.username, Some(admin.id), ip, "mfa_required").await;
+
+        return Ok(Json(LoginOutcome::MfaRequired(MfaChallenge {
+            mfa_required: true,
+            mfa_token,
+            expires_at,
+        })));
+    }
+
+    record_login_event(&state, &admin.username, Some(admin.id), ip, "oidc_success").await;
+
+    let mut conn = state.pool.acquire().await?;
+    let response = issue_tokens(
+        &mut conn,
+        &state.jwt_keys,
+        &state.config,
+        admin,
+        Uuid::new_v4(),
+        &meta,
+    )
+    .await?;
+
+    Ok(Json(LoginOutcome::Authenticated(response)))
+}
+
+/// Link an external identity to an admin, unless it belongs to someone else
+async fn link_identity(
+    conn: &mut PgConnection,
+    meta: &RequestMeta,
+    admin_id: Uuid,
+    provider: &str,
+    identity: &Identity,
+) -> Result<(), AppError> {
+    let linked = sqlx::query_as::<_, AdminIdentity>(
+        "INSERT INTO admin_identities (admin_id, provider, issuer, subject, email)
+         VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT (issuer, subject) DO NOTHING
+         RETURNING id, provider, issuer, subject, email, last_login_at, created_at",
+    )
+    .bind(admin_id)
+    .bind(provider)
+    .bind(&identity.issuer)
+    .bind(&identity.subject)
+    .bind(&identity.email)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let Some(linked) = linked else {
+        let owner: Uuid = sqlx::query_scalar(
+            "SELECT admin_id FROM admin_identities WHERE issuer = $1 AND subject = $2",
+        )
+        .bind(&identity.issuer)
+        .bind(&identity.subject)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        return if owner == admin_id {
+            Ok(())
+        } else {
+            Err(AppError::Conflict(ErrorCode::IdentityAlreadyLinked))
+        };
+    };
+
+    let username: String = sqlx::query_scalar("SELECT username FROM admins WHERE id = $1")
+        .bind(admin_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    AuditEvent::with_actor(admin_id, &username, "link_identity", "admin", admin_id)
+        .after(&linked)
+        .record(&mut *conn, meta)
+        .await
+}
+
+/// List the external identities linked to the caller's account
+#[utoipa::path(
+    get,
+    path = "/identities",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, body = Vec<AdminIdentity>),
+        (status = 403, description = "Requires a login session, not an API token"),
+    )
+)]
+async fn list_identities(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_session()?;
+
+    let identities = sqlx::query_as::<_, AdminIdentity>(
+        "SELECT id, provider, issuer, subject, email, last_login_at, created_at
+         FROM admin_identities WHERE admin_id = $1 ORDER BY created_at ASC",
+    )
+    .bind(auth.user_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(identities))
+}
+
+/// Unlink one of the caller's external identities
+#[utoipa::path(
+    delete,
+    path = "/identities/{id}",
+    params(("id" = Uuid, Path, description = "Identity id")),
+    security(("bearer" = [])),
+    responses(
+        (status = 204, description = "Unlinked"),
+        (status = 403, description = "Requires a login session, not an API token"),
+        (status = 404, description = "No such identity of the caller's"),
+    )
+)]
+async fn unlink_identity(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    meta: RequestMeta,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    auth.require_session()?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let identity = sqlx::query_as::<_, AdminIdentity>(
+        "DELETE FROM admin_identities WHERE id = $1 AND admin_id = $2
+         RETURNING id, provider, issuer, subject, email, last_login_at, created_at",
+    )
+    .bind(id)
+    .bind(auth.user_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound(ErrorCode::IdentityNotFound))?;
+
+    AuditEvent::new(&auth, "unlink_identity", "admin", auth.user_id)
+        .before(&identity)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Check a password against a stored Argon2 hash
+fn verify_password(password_hash: &str, password: &str) -> Result<(), AppError> {
+    let parsed_hash = PasswordHash::new(password_hash)
+        .map_err(|_| AppError::InternalError("Invalid password hash".to_string()))?;
+
+    Argon2::default()
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .map_err(|_| AppError::InvalidCredentials)
+}
+
+/// Check a TOTP code or consume a recovery code for an admin with 2FA enabled
+async fn verify_second_factor(
+    pool: &PgPool,
+    admin_id: Uuid,
+    code: Option<&str>,
+    recovery_code: Option<&str>,
+) -> Result<(), AppError> {
+    if let Some(code) = code {
+        let (secret, last_used_step): (Option<String>, Option<i64>) =
+            sqlx::query_as("SELECT totp_secret, totp_last_used_step FROM admins WHERE id = $1")
+                .bind(admin_id)
+                .fetch_one(pool)
+                .await?;
+
+        let secret = secret.ok_or(AppError::InvalidCredentials)?;
+        let step = totp::verify(&secret, code, Utc::now().timestamp(), last_used_step)
+            .ok_or(AppError::InvalidCredentials)?;
+
+        // Record the step atomically so the same code can't be used twice
+        let updated = sqlx::query(
+            "UPDATE admins SET totp_last_used_step = $1
+             WHERE id = $2 AND (totp_last_used_step IS NULL OR totp_last_used_step < $1)",
+        )
+        .bind(step)
+        .bind(admin_id)
+        .execute(pool)
+        .await?;
+
+        if updated.rows_affected() == 0 {
+            return Err(AppError::InvalidCredentials);
+        }
+
+        return Ok(());
+    }
+
+    if let Some(recovery_code) = recovery_code {
+        let used: Option<Uuid> = sqlx::query_scalar(
+            "UPDATE admin_recovery_codes SET used_at = NOW()
+             WHERE admin_id = $1 AND code_hash = $2 AND used_at IS NULL
+             RETURNING id",
+        )
+        .bind(admin_id)
+        .bind(hash_token(&totp::normalize_recovery_code(recovery_code)))
+        .fetch_optional(pool)
+        .await?;
+
+        return used.map(|_| ()).ok_or(AppError::InvalidCredentials);
+    }
+
+    Err(AppError::BadRequest(ErrorCode::TotpCodeRequired))
+}
+
+/// Hash of a random password, verified against when the username doesn't exist
+fn dummy_password_hash() -> &'static str {
+    static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+
+    DUMMY_HASH.get_or_init(|| hash_password(&generate_opaque_token()).unwrap_or_default())
+}
+
+fn too_many_requests(wait: std::time::Duration) -> AppError {
+    AppError::TooManyRequests {
+        retry_after: wait.as_millis().div_ceil(1000).max(1) as u64,
+    }
+}
+
+/// Count a failed attempt in memory and, for existing accounts, towards the lockout
+async fn record_login_failure(
+    state: &AppState,
+    username: &str,
+    admin: Option<&Admin>,
+    ip: Option<IpAddr>,
+) -> Result<(), AppError> {
+    state.login_throttle.record_failure(username, ip);
+
+    let Some(admin) = admin else {
+        return Ok(());
+    };
+
+    let locked_until: Option<DateTime<Utc>> = sqlx::query_scalar(
+        "UPDATE admins
+         SET failed_login_attempts = failed_login_attempts + 1,
+             locked_until = CASE
+                 WHEN failed_login_attempts + 1 >= $2 THEN NOW() + make_interval(secs => $3)
+                 ELSE locked_until
+             END
+         WHERE id = $1
+         RETURNING locked_until",
+    )
+    .bind(admin.id)
+    .bind(state.config.login.max_failures)
+    .bind(state.config.login.lockout_seconds as f64)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if let Some(locked_until) = locked_until.filter(|until| *until > Utc::now()) {
+        tracing::warn!(
+            "Account {} locked until {} after repeated login failures",
+            admin.username,
+            locked_until
+        );
+    }
+
+    Ok(())
+}
+
+async fn reset_login_failures(state: &AppState, admin: &Admin) -> Result<(), AppError> {
+    state.login_throttle.record_success(&admin.username);
+
+    sqlx::query(
+        "UPDATE admins SET failed_login_attempts = 0, locked_until = NULL
+         WHERE id = $1 AND (failed_login_attempts > 0 OR locked_until IS NOT NULL)",
+    )
+    .bind(admin.id)
+    .execute(&state.pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Record a login attempt; failures to record are logged but never fail the login
+async fn record_login_event(
+    state: &AppState,
+    username: &str,
+    admin_id: Option<Uuid>,
+    ip: Option<IpAddr>,
+    outcome: &str,
+) {
+    state.metrics.login(outcome);
+
+    let result = sqlx::query(
+        "INSERT INTO login_events (username, admin_id, ip, outcome) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(username)
+    .bind(admin_id)
+    .bind(ip.map(|ip| ip.to_string()))
+    .bind(outcome)
+    .execute(&state.pool)
+    .await;
+
+    if let Err(e) = result {
+        tracing::warn!("Failed to record login event: {:?}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::revocation::RevocationList;
+
+    /// Pool for the database in `DATABASE_URL`, migrated
+    async fn test_pool() -> PgPool {
+        let url = std::env::var("DATABASE_URL").expect("DATABASE_URL is not set");
+        let pool = PgPool::connect(&url).await.unwrap();
+        crate::db::migrations::run(&pool, false).await.unwrap();
+        pool
+    }
+
+    async fn create_admin(pool: &PgPool) -> Uuid {
+        sqlx::query_scalar(
+            "INSERT INTO admins (username, password_hash, role, totp_secret, totp_enabled_at)
+             VALUES ($1, '', 'viewer', $2, NOW()) RETURNING id",
+        )
+        .bind(format!("test-{}", Uuid::new_v4()))
+        .bind(totp::generate_secret())
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn recovery_codes_match_once() {
+        let pool = test_pool().await;
+        let admin_id = create_admin(&pool).await;
+
+        let code = totp::generate_recovery_code();
+        sqlx::query("INSERT INTO admin_recovery_codes (admin_id, code_hash) VALUES ($1, $2)")
+            .bind(admin_id)
+            .bind(hash_token(&totp::normalize_recovery_code(&code)))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let first = verify_second_factor(&pool, admin_id, None, Some(&code.to_uppercase())).await;
+        let second = verify_second_factor(&pool, admin_id, None, Some(&code)).await;
+        let missing = verify_second_factor(&pool, admin_id, None, None).await;
+
+        sqlx::query("DELETE FROM admins WHERE id = $1")
+            .bind(admin_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(first.is_ok(), "{:?}", first);
+        assert!(matches!(second, Err(AppError::InvalidCredentials)));
+        assert!(matches!(
+            missing,
+            Err(AppError::BadRequest(ErrorCode::TotpCodeRequired))
+        ));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn totp_codes_match_once() {
+        let pool = test_pool().await;
+        let admin_id = create_admin(&pool).await;
+
+        let secret: String = sqlx::query_scalar("SELECT totp_secret FROM admins WHERE id = $1")
+            .bind(admin_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let code = totp::code_now(&secret);
+
+        let first = verify_second_factor(&pool, admin_id, Some(&code), None).await;
+        let replayed = verify_second_factor(&pool, admin_id, Some(&code), None).await;
+
+        sqlx::query("DELETE FROM admins WHERE id = $1")
+            .bind(admin_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(first.is_ok(), "{:?}", first);
+        assert!(matches!(replayed, Err(AppError::InvalidCredentials)));
+    }
+
+    /// Exchange a refresh token, returning the rotated one
+    async fn refresh_with(state: &AppState, refresh_token: &str) -> Result<String, AppError> {
+        let response = refresh(
+            State(state.clone()),
+            RequestMeta::default(),
+            Json(RefreshRequest {
+                refresh_token: refresh_token.to_string(),
+            }),
+        )
+        .await?
+        .into_response();
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        Ok(body["refresh_token"].as_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    #[ignore = "needs DATABASE_URL"]
+    async fn replayed_refresh_token_revokes_the_family() {
+        let pool = test_pool().await;
+        let state = AppState {
+            pool: pool.clone(),
+            revocations: RevocationList::new(pool.clone()),
+            ..AppState::for_tests()
+        };
+        let admin_id = create_admin(&pool).await;
+        let admin = sqlx::query_as::<_, Admin>(
+            "SELECT id, username, password_hash, role, disabled_at, totp_enabled_at, locked_until, created_at FROM admins WHERE id = $1",
+        )
+        .bind(admin_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        let family_id = Uuid::new_v4();
+        let mut conn = pool.acquire().await.unwrap();
+        let login = issue_tokens(
+            &mut conn,
+            &state.jwt_keys,
+            &state.config,
+            admin,
+            family_id,
+            &RequestMeta::default(),
+        )
+        .await
+        .unwrap();
+        drop(conn);
+
+        let rotated = refresh_with(&state, &login.refresh_token).await;
+        let replayed = refresh_with(&state, &login.refresh_token).await;
+        let newest = match &rotated {
+            Ok(token) => Some(refresh_with(&state, token).await),
+            Err(_) => None,
+        };
+        let live: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM refresh_tokens WHERE family_id = $1 AND revoked_at IS NULL",
+        )
+        .bind(family_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query("DELETE FROM admins WHERE id = $1")
+            .bind(admin_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(rotated.is_ok(), "{:?}", rotated);
+        assert!(matches!(replayed, Err(AppError::InvalidToken)));
+        assert!(matches!(newest, Some(Err(AppError::InvalidToken))));
+        assert_eq!(live, 0);
+    }
+}
@@ -1,19 +1,18 @@
-use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
-    routing::{delete, get, post, put},
-    Json, Router,
-};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Router};
 use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi};
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    db::AppState,
-    error::AppError,
-    middleware::auth::AuthUser,
-    models::{BlogPost, CreateBlogPost, UpdateBlogPost},
+    db::{audit::AuditEvent, AppState},
+    error::{AppError, ErrorCode},
+    middleware::{
+        auth::{Author, Editor, RequireRole},
+        extract::{Json, Path, Query},
+        request_meta::RequestMeta,
+    },
+    models::{BlogPost, CreateBlogPost, Scope, UpdateBlogPost},
 };
 
 pub fn router() -> Router<AppState> {
@@ -22,11 +21,17 @@ pub fn router() -> Router<AppState> {
         .route("/:slug", get(get_post).put(update_post).delete(delete_post))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(OpenApi)]
+#[openapi(paths(list_posts, create_post, get_post, update_post, delete_post))]
+pub struct BlogApi;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct ListQuery {
     #[serde(default)]
     published: Option<bool>,
     #[serde(default = "default_limit")]
+    #[param(default = default_limit)]
     limit: i64,
     #[serde(default)]
     offset: i64,
@@ -37,6 +42,12 @@ fn default_limit() -> i64 {
 }
 
 /// List all blog posts
+#[utoipa::path(
+    get,
+    path = "/",
+    params(ListQuery),
+    responses((status = 200, description = "Posts, most recently published first", body = Vec<BlogPost>))
+)]
 async fn list_posts(
     State(state): State<AppState>,
     Query(query): Query<ListQuery>,
@@ -61,6 +72,15 @@ async fn list_posts(
 }
 
 /// Get a single blog post by slug
+#[utoipa::path(
+    get,
+    path = "/{slug}",
+    params(("slug" = String, Path, description = "Post slug")),
+    responses(
+        (status = 200, body = BlogPost),
+        (status = 404, description = "No post with this slug"),
+    )
+)]
 async fn get_post(
     State(state): State<AppState>,
     Path(slug): Path<String>,
@@ -69,18 +89,35 @@ async fn get_post(
         "SELECT id, slug, title, content, excerpt, tags, published, view_count, published_at, created_at, updated_at FROM blog_posts WHERE slug = $1"
     )
     .bind(&slug)
-    .fetch_one(&state.pool)
-    .await?;
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(AppError::NotFound(ErrorCode::BlogPostNotFound))?;
+
+    state.metrics.view("blog_post");
 
     Ok(Json(post))
 }
 
-/// Create a new blog post (requires authentication)
+/// Create a new blog post (requires author role)
+#[utoipa::path(
+    post,
+    path = "/",
+    request_body = CreateBlogPost,
+    security(("bearer" = ["blog:write"])),
+    responses(
+        (status = 201, body = BlogPost),
+        (status = 400, description = "Invalid post"),
+        (status = 403, description = "Requires the author role"),
+    )
+)]
 async fn create_post(
     State(state): State<AppState>,
-    _auth: AuthUser,
+    RequireRole(auth, _): RequireRole<Author>,
+    meta: RequestMeta,
     Json(payload): Json<CreateBlogPost>,
 ) -> Result<impl IntoResponse, AppError> {
+    auth.require_scope(Scope::BlogWrite)?;
+
     payload.validate()?;
 
     let tags: Vec<String> = payload.tags.unwrap_or_default();
@@ -114,6 +151,7 @@ async fn create_post(
         slug
     };
 
+    let mut tx = state.pool.begin().await?;
     let post = sqlx::query_as::<_, BlogPost>(
         "INSERT INTO blog_posts (slug, title, content, excerpt, tags, published, published_at)
          VALUES ($1, $2, $3, $4, $5, $6, $7)
@@ -126,33 +164,56 @@ async fn create_post(
     .bind(&tags)
     .bind(payload.published.unwrap_or(false))
     .bind(payload.published_at)
-    .fetch_one(&state.pool)
+    .fetch_one(&mut *tx)
     .await?;
 
+    AuditEvent::new(&auth, "create", "blog_post", post.id)
+        .after(&post)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
+
     Ok((StatusCode::CREATED, Json(post)))
 }
 
-/// Update a blog post (requires authentication)
+/// Update a blog post (requires author role)
+#[utoipa::path(
+    put,
+    path = "/{slug}",
+    params(("slug" = String, Path, description = "Post slug")),
+    request_body = UpdateBlogPost,
+    security(("bearer" = ["blog:write"])),
+    responses(
+        (status = 200, body = BlogPost),
+        (status = 403, description = "Requires the author role"),
+        (status = 404, description = "No post with this slug"),
+    )
+)]
 async fn update_post(
     State(state): State<AppState>,
-    _auth: AuthUser,
+    RequireRole(auth, _): RequireRole<Author>,
+    meta: RequestMeta,
     Path(slug): Path<String>,
     Json(payload): Json<UpdateBlogPost>,
 ) -> Result<impl IntoResponse, AppError> {
+    auth.require_scope(Scope::BlogWrite)?;
+
+    let mut tx = state.pool.begin().await?;
+
     // First, get the existing post
     let existing = sqlx::query_as::<_, BlogPost>(
-        "SELECT id, slug, title, content, excerpt, tags, published, view_count, published_at, created_at, updated_at FROM blog_posts WHERE slug = $1"
+        "SELECT id, slug, title, content, excerpt, tags, published, view_count, published_at, created_at, updated_at FROM blog_posts WHERE slug = $1 FOR UPDATE"
     )
     .bind(&slug)
-    .fetch_optional(&state.pool)
+    .fetch_optional(&mut *tx)
     .await?
-    .ok_or_else(|| AppError::NotFound("Blog post not found".to_string()))?;
+    .ok_or_else(|| AppError::NotFound(ErrorCode::BlogPostNotFound))?;
 
     // Merge with existing values
-    let title = payload.title.unwrap_or(existing.title);
-    let content = payload.content.unwrap_or(existing.content);
-    let excerpt = payload.excerpt.or(existing.excerpt);
-    let tags = payload.tags.unwrap_or(existing.tags);
+    let title = payload.title.unwrap_or_else(|| existing.title.clone());
+    let content = payload.content.unwrap_or_else(|| existing.content.clone());
+    let excerpt = payload.excerpt.or_else(|| existing.excerpt.clone());
+    let tags = payload.tags.unwrap_or_else(|| existing.tags.clone());
     let published = payload.published.unwrap_or(existing.published);
     let published_at = payload.published_at.or(existing.published_at);
 
@@ -167,22 +228,55 @@ async fn update_post(
     .bind(published)
     .bind(published_at)
     .bind(&slug)
-    .fetch_one(&state.pool)
+    .fetch_one(&mut *tx)
     .await?;
 
+    AuditEvent::new(&auth, "update", "blog_post", post.id)
+        .before(&existing)
+        .after(&post)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
+
     Ok(Json(post))
 }
 
-/// Delete a blog post (requires authentication)
+/// Delete a blog post (requires editor role)
+#[utoipa::path(
+    delete,
+    path = "/{slug}",
+    params(("slug" = String, Path, description = "Post slug")),
+    security(("bearer" = ["blog:write"])),
+    responses(
+        (status = 204, description = "Deleted, or did not exist"),
+        (status = 403, description = "Requires the editor role"),
+    )
+)]
 async fn delete_post(
     State(state): State<AppState>,
-    _auth: AuthUser,
+    RequireRole(auth, _): RequireRole<Editor>,
+    meta: RequestMeta,
     Path(slug): Path<String>,
 ) -> Result<StatusCode, AppError> {
-    sqlx::query("DELETE FROM blog_posts WHERE slug = $1")
-        .bind(&slug)
-        .execute(&state.pool)
-        .await?;
+    auth.require_scope(Scope::BlogWrite)?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let deleted = sqlx::query_as::<_, BlogPost>(
+        "DELETE FROM blog_posts WHERE slug = $1
+         RETURNING id, slug, title, content, excerpt, tags, published, view_count, published_at, created_at, updated_at",
+    )
+    .bind(&slug)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if let Some(post) = deleted {
+        AuditEvent::new(&auth, "delete", "blog_post", post.id)
+            .before(&post)
+            .record(&mut *tx, &meta)
+            .await?;
+    }
+    tx.commit().await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
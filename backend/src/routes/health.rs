@@ -0,0 +1,168 @@
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use crate::db::{pool, AppState};
+
+/// How long readiness waits for the database before reporting it down
+const DATABASE_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        // `/health` predates the split and stays a liveness probe
+        .route("/", get(live))
+        .route("/live", get(live))
+        .route("/ready", get(ready))
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Ok,
+    Degraded,
+}
+
+#[derive(Debug, Serialize)]
+struct Readiness {
+    status: Status,
+    database: DatabaseCheck,
+    migrations: MigrationCheck,
+    pool: PoolCheck,
+}
+
+#[derive(Debug, Serialize)]
+struct DatabaseCheck {
+    ok: bool,
+    latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    postgres_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct MigrationCheck {
+    ok: bool,
+    applied: Option<i64>,
+    expected: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct PoolCheck {
+    saturated: bool, // informational; a briefly full pool just queues requests
+    size: u32,
+    idle: usize,
+    max: u32,
+    saturation: f64, // connections in use over the configured maximum
+}
+
+/// Liveness: the process is up and serving requests, nothing else is checked
+async fn live() -> impl IntoResponse {
+    (
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(serde_json::json!({
+            "status": "ok",
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        })),
+    )
+}
+
+/// Readiness: the database answers in time and migrations are current.
+/// Responds 503 when either check fails; pool usage is reported but doesn't
+/// affect readiness.
+async fn ready(State(state): State<AppState>) -> impl IntoResponse {
+    // Sampled before the probe checks out a connection of its own
+    let size = state.pool.size();
+    let idle = state.pool.num_idle();
+
+    let started = Instant::now();
+    let probe = tokio::time::timeout(DATABASE_TIMEOUT, async {
+        pool::test_connection(&state.pool).await?;
+        let version = pool::get_db_version(&state.pool).await?;
+        let applied = pool::schema_version(&state.pool).await?;
+        Ok::<_, sqlx::Error>((version, applied))
+    })
+    .await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let (database, applied) = match probe {
+        Ok(Ok((version, applied))) => (
+            DatabaseCheck {
+                ok: true,
+                latency_ms,
+                postgres_version: Some(version),
+                error: None,
+            },
+            applied,
+        ),
+        Ok(Err(e)) => {
+            tracing::warn!("Readiness check failed: {:?}", e);
+            (
+                DatabaseCheck {
+                    ok: false,
+                    latency_ms,
+                    postgres_version: None,
+                    error: Some("query failed".to_string()),
+                },
+                None,
+            )
+        }
+        Err(_) => {
+            tracing::warn!("Readiness check timed out after {:?}", DATABASE_TIMEOUT);
+            (
+                DatabaseCheck {
+                    ok: false,
+                    latency_ms,
+                    postgres_version: None,
+                    error: Some("timed out".to_string()),
+                },
+                None,
+            )
+        }
+    };
+
+    let expected = pool::MIGRATOR
+        .iter()
+        .map(|migration| migration.version)
+        .max();
+    let migrations = MigrationCheck {
+        ok: database.ok && applied >= expected,
+        applied,
+        expected,
+    };
+
+    let max = state.config.database.max_connections;
+    let in_use = (size as usize).saturating_sub(idle);
+    let saturation = in_use as f64 / f64::from(max.max(1));
+    let pool = PoolCheck {
+        saturated: in_use >= max as usize,
+        size,
+        idle,
+        max,
+        saturation,
+    };
+
+    let healthy = database.ok && migrations.ok;
+    let (code, status) = if healthy {
+        (StatusCode::OK, Status::Ok)
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, Status::Degraded)
+    };
+
+    (
+        code,
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(Readiness {
+            status,
+            database,
+            migrations,
+            pool,
+        }),
+    )
+}
@@ -0,0 +1,38 @@
+use axum::{
+    extract::State,
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+
+use crate::{auth::totp::constant_time_eq, db::AppState, error::AppError};
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/metrics", get(metrics))
+}
+
+/// Prometheus scrape endpoint; requires `metrics.token` as a bearer token when set
+async fn metrics(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    if let Some(expected) = &state.config.metrics.token {
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or(AppError::Unauthorized)?;
+
+        if !constant_time_eq(token.as_bytes(), expected.as_bytes()) {
+            return Err(AppError::InvalidToken);
+        }
+    }
+
+    let body = state
+        .metrics
+        .render(&state.pool)
+        .map_err(|e| AppError::InternalError(format!("Failed to render metrics: {}", e)))?;
+
+    Ok(([(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)], body))
+}
@@ -1,4 +1,72 @@
+use axum::{Extension, Router};
+use utoipa::OpenApi;
+
+use crate::{db::AppState, middleware::api_version::ApiVersion};
+
+pub mod admins;
 pub mod apps;
+pub mod audit;
 pub mod auth;
 pub mod blog;
+pub mod health;
+pub mod metrics;
 pub mod novels;
+pub mod tokens;
+pub mod well_known;
+
+/// A group of routes nested under each API version, with its OpenAPI description
+pub struct ApiModule {
+    pub prefix: &'static str,
+    pub router: fn() -> Router<AppState>,
+    pub openapi: fn() -> utoipa::openapi::OpenApi,
+}
+
+/// Everything served under `/api/v{n}`; the routers and the spec are both built from this
+pub const API_MODULES: &[ApiModule] = &[
+    ApiModule {
+        prefix: "/novels",
+        router: novels::router,
+        openapi: novels::NovelsApi::openapi,
+    },
+    ApiModule {
+        prefix: "/blog",
+        router: blog::router,
+        openapi: blog::BlogApi::openapi,
+    },
+    ApiModule {
+        prefix: "/apps",
+        router: apps::router,
+        openapi: apps::AppsApi::openapi,
+    },
+    ApiModule {
+        prefix: "/auth",
+        router: auth::router,
+        openapi: auth::AuthApi::openapi,
+    },
+    ApiModule {
+        prefix: "/auth/tokens",
+        router: tokens::router,
+        openapi: tokens::TokensApi::openapi,
+    },
+    ApiModule {
+        prefix: "/admins",
+        router: admins::router,
+        openapi: admins::AdminsApi::openapi,
+    },
+    ApiModule {
+        prefix: "/audit",
+        router: audit::router,
+        openapi: audit::AuditApi::openapi,
+    },
+];
+
+/// Router with every API module nested at its prefix, serving `version`.
+/// Handlers whose response shape differs between versions take `ApiVersion`.
+pub fn api_router(version: ApiVersion) -> Router<AppState> {
+    API_MODULES
+        .iter()
+        .fold(Router::new(), |router, module| {
+            router.nest(module.prefix, (module.router)())
+        })
+        .layer(Extension(version))
+}
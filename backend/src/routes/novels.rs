@@ -1,21 +1,22 @@
-use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
-    routing::{delete, get, post, put},
-    Json, Router,
-};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Router};
 use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi};
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    db::AppState,
-    error::AppError,
-    middleware::auth::AuthUser,
+    db::{audit::AuditEvent, AppState},
+    error::{AppError, ErrorCode},
+    middleware::{
+        auth::{Author, Editor, RequireRole},
+        extract::{Json, Path, Query},
+        locale::Lang,
+        request_meta::RequestMeta,
+    },
     models::{
-        get_all_genres, get_all_novel_types, AddRelatedNovel, CreateChapter, CreateNovel, Novel,
-        NovelChapter, RelatedNovel, UpdateChapter, UpdateNovel,
+        get_all_genres, get_all_novel_types, AddRelatedNovel, CreateChapter, CreateNovel,
+        GenreInfo, Novel, NovelChapter, NovelList, NovelTypeInfo, NovelWithStats, RelatedNovel,
+        Scope, UpdateChapter, UpdateNovel,
     },
 };
 
@@ -41,7 +42,28 @@ pub fn router() -> Router<AppState> {
         )
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_novels,
+    create_novel,
+    list_genres,
+    list_novel_types,
+    get_novel,
+    update_novel,
+    delete_novel,
+    list_chapters,
+    create_chapter,
+    get_chapter,
+    update_chapter,
+    delete_chapter,
+    list_relations,
+    add_relation,
+    remove_relation,
+))]
+pub struct NovelsApi;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct ListQuery {
     #[serde(default)]
     status: Option<String>,
@@ -50,6 +72,7 @@ struct ListQuery {
     #[serde(default)]
     genre: Option<String>,
     #[serde(default = "default_limit")]
+    #[param(default = default_limit)]
     limit: i64,
     #[serde(default)]
     offset: i64,
@@ -87,11 +110,7 @@ async fn ensure_unique_slug(pool: &sqlx::PgPool, base_slug: &str) -> Result<Stri
 
         if counter > 100 {
             // Safety limit
-            slug = format!(
-                "{}-{}",
-                base_slug,
-                Uuid::new_v4().to_string()[..8].to_string()
-            );
+            slug = format!("{}-{}", base_slug, &Uuid::new_v4().to_string()[..8]);
             break;
         }
     }
@@ -100,16 +119,34 @@ async fn ensure_unique_slug(pool: &sqlx::PgPool, base_slug: &str) -> Result<Stri
 }
 
 /// List all genres
-async fn list_genres() -> impl IntoResponse {
-    Json(get_all_genres())
+#[utoipa::path(
+    get,
+    path = "/genres",
+    params(Lang),
+    responses((status = 200, description = "Genres with localized names", body = Vec<GenreInfo>))
+)]
+async fn list_genres(lang: Lang) -> impl IntoResponse {
+    (lang, Json(get_all_genres(lang.0)))
 }
 
 /// List all novel types
-async fn list_novel_types() -> impl IntoResponse {
-    Json(get_all_novel_types())
+#[utoipa::path(
+    get,
+    path = "/types",
+    params(Lang),
+    responses((status = 200, description = "Novel types with localized names", body = Vec<NovelTypeInfo>))
+)]
+async fn list_novel_types(lang: Lang) -> impl IntoResponse {
+    (lang, Json(get_all_novel_types(lang.0)))
 }
 
 /// List all novels
+#[utoipa::path(
+    get,
+    path = "/",
+    params(ListQuery),
+    responses((status = 200, description = "Novels, newest first", body = NovelList))
+)]
 async fn list_novels(
     State(state): State<AppState>,
     Query(query): Query<ListQuery>,
@@ -184,13 +221,19 @@ async fn list_novels(
 
     let total: i64 = count_query.fetch_one(&state.pool).await.unwrap_or(0);
 
-    Ok(Json(serde_json::json!({
-        "novels": novels,
-        "total": total
-    })))
+    Ok(Json(NovelList { novels, total }))
 }
 
 /// Get a single novel by slug with related novels
+#[utoipa::path(
+    get,
+    path = "/{slug}",
+    params(("slug" = String, Path, description = "Novel slug")),
+    responses(
+        (status = 200, body = NovelWithStats),
+        (status = 404, description = "No novel with this slug"),
+    )
+)]
 async fn get_novel(
     State(state): State<AppState>,
     Path(slug): Path<String>,
@@ -200,8 +243,11 @@ async fn get_novel(
          FROM novels WHERE slug = $1",
     )
     .bind(&slug)
-    .fetch_one(&state.pool)
-    .await?;
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(AppError::NotFound(ErrorCode::NovelNotFound))?;
+
+    state.metrics.view("novel");
 
     // Get related novels
     let related_novels = sqlx::query_as::<_, RelatedNovel>(
@@ -224,29 +270,33 @@ async fn get_novel(
             .await
             .unwrap_or(0);
 
-    Ok(Json(serde_json::json!({
-        "id": novel.id,
-        "slug": novel.slug,
-        "title": novel.title,
-        "description": novel.description,
-        "novel_type": novel.novel_type,
-        "genre": novel.genre,
-        "genres": novel.genres,
-        "status": novel.status,
-        "view_count": novel.view_count,
-        "created_at": novel.created_at,
-        "updated_at": novel.updated_at,
-        "chapter_count": chapter_count,
-        "related_novels": related_novels
-    })))
+    Ok(Json(NovelWithStats {
+        novel,
+        chapter_count,
+        related_novels: Some(related_novels),
+    }))
 }
 
-/// Create a new novel (requires authentication)
+/// Create a new novel (requires author role)
+#[utoipa::path(
+    post,
+    path = "/",
+    request_body = CreateNovel,
+    security(("bearer" = ["novels:write"])),
+    responses(
+        (status = 201, body = Novel),
+        (status = 400, description = "Invalid novel"),
+        (status = 403, description = "Requires the author role"),
+    )
+)]
 async fn create_novel(
     State(state): State<AppState>,
-    _auth: AuthUser,
+    RequireRole(auth, _): RequireRole<Author>,
+    meta: RequestMeta,
     Json(payload): Json<CreateNovel>,
 ) -> Result<impl IntoResponse, AppError> {
+    auth.require_scope(Scope::NovelsWrite)?;
+
     payload.validate()?;
 
     // Always generate UUID-based slug (ignore any provided slug)
@@ -258,6 +308,7 @@ async fn create_novel(
     let status = payload.status.unwrap_or_else(|| "draft".to_string());
     let genres = payload.genres.unwrap_or_default();
 
+    let mut tx = state.pool.begin().await?;
     let novel = sqlx::query_as::<_, Novel>(
         "INSERT INTO novels (slug, title, description, novel_type, genres, status)
          VALUES ($1, $2, $3, $4, $5, $6)
@@ -269,19 +320,41 @@ async fn create_novel(
     .bind(&novel_type)
     .bind(&genres)
     .bind(&status)
-    .fetch_one(&state.pool)
+    .fetch_one(&mut *tx)
     .await?;
 
+    AuditEvent::new(&auth, "create", "novel", novel.id)
+        .after(&novel)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
+
     Ok((StatusCode::CREATED, Json(novel)))
 }
 
-/// Update a novel (requires authentication)
+/// Update a novel (requires author role)
+#[utoipa::path(
+    put,
+    path = "/{slug}",
+    params(("slug" = String, Path, description = "Novel slug")),
+    request_body = UpdateNovel,
+    security(("bearer" = ["novels:write"])),
+    responses(
+        (status = 200, body = Novel),
+        (status = 400, description = "Invalid or empty update"),
+        (status = 403, description = "Requires the author role"),
+        (status = 404, description = "No novel with this slug"),
+    )
+)]
 async fn update_novel(
     State(state): State<AppState>,
-    _auth: AuthUser,
+    RequireRole(auth, _): RequireRole<Author>,
+    meta: RequestMeta,
     Path(slug): Path<String>,
     Json(payload): Json<UpdateNovel>,
 ) -> Result<impl IntoResponse, AppError> {
+    auth.require_scope(Scope::NovelsWrite)?;
+
     // Build dynamic update query
     let mut updates = Vec::<String>::new();
     let mut param_idx = 0;
@@ -308,7 +381,7 @@ async fn update_novel(
     }
 
     if updates.is_empty() {
-        return Err(AppError::BadRequest("No fields to update".to_string()));
+        return Err(AppError::BadRequest(ErrorCode::NoFieldsToUpdate));
     }
 
     updates.push("updated_at = NOW()".to_string());
@@ -341,21 +414,65 @@ async fn update_novel(
 
     query = query.bind(&slug);
 
-    let novel = query.fetch_one(&state.pool).await?;
+    let mut tx = state.pool.begin().await?;
+
+    let before = sqlx::query_as::<_, Novel>(
+        "SELECT id, slug, title, description, novel_type, genre, genres, status, view_count, created_at, updated_at
+         FROM novels WHERE slug = $1 FOR UPDATE",
+    )
+    .bind(&slug)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound(ErrorCode::NovelNotFound))?;
+
+    let novel = query.fetch_one(&mut *tx).await?;
+
+    AuditEvent::new(&auth, "update", "novel", novel.id)
+        .before(&before)
+        .after(&novel)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
 
     Ok(Json(novel))
 }
 
-/// Delete a novel (requires authentication)
+/// Delete a novel (requires editor role)
+#[utoipa::path(
+    delete,
+    path = "/{slug}",
+    params(("slug" = String, Path, description = "Novel slug")),
+    security(("bearer" = ["novels:write"])),
+    responses(
+        (status = 204, description = "Deleted, or did not exist"),
+        (status = 403, description = "Requires the editor role"),
+    )
+)]
 async fn delete_novel(
     State(state): State<AppState>,
-    _auth: AuthUser,
+    RequireRole(auth, _): RequireRole<Editor>,
+    meta: RequestMeta,
     Path(slug): Path<String>,
 ) -> Result<StatusCode, AppError> {
-    sqlx::query("DELETE FROM novels WHERE slug = $1")
-        .bind(&slug)
-        .execute(&state.pool)
-        .await?;
+    auth.require_scope(Scope::NovelsWrite)?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let deleted = sqlx::query_as::<_, Novel>(
+        "DELETE FROM novels WHERE slug = $1
+         RETURNING id, slug, title, description, novel_type, genre, genres, status, view_count, created_at, updated_at",
+    )
+    .bind(&slug)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    if let Some(novel) = deleted {
+        AuditEvent::new(&auth, "delete", "novel", novel.id)
+            .before(&novel)
+            .record(&mut *tx, &meta)
+            .await?;
+    }
+    tx.commit().await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -363,6 +480,14 @@ async fn delete_novel(
 // Related novels endpoints
 
 /// List related novels
+#[utoipa::path(
+    get,
+    path = "/{slug}/relations",
+    params(("slug" = String, Path, description = "Novel slug")),
+    responses(
+        (status = 200, description = "Related novels by title", body = Vec<RelatedNovel>),
+    )
+)]
 async fn list_relations(
     State(state): State<AppState>,
     Path(slug): Path<String>,
@@ -382,12 +507,28 @@ async fn list_relations(
 }
 
 /// Add a related novel
+#[utoipa::path(
+    post,
+    path = "/{slug}/relations",
+    params(("slug" = String, Path, description = "Novel slug")),
+    request_body = AddRelatedNovel,
+    security(("bearer" = ["novels:write"])),
+    responses(
+        (status = 201, description = "Added, or relation type changed"),
+        (status = 400, description = "Invalid relation"),
+        (status = 403, description = "Requires the author role"),
+        (status = 404, description = "Either novel does not exist"),
+    )
+)]
 async fn add_relation(
     State(state): State<AppState>,
-    _auth: AuthUser,
+    RequireRole(auth, _): RequireRole<Author>,
+    meta: RequestMeta,
     Path(slug): Path<String>,
     Json(payload): Json<AddRelatedNovel>,
 ) -> Result<impl IntoResponse, AppError> {
+    auth.require_scope(Scope::NovelsWrite)?;
+
     payload.validate()?;
 
     let relation_type = payload
@@ -397,13 +538,17 @@ async fn add_relation(
     // Get both novel IDs
     let novel_id: (Uuid,) = sqlx::query_as("SELECT id FROM novels WHERE slug = $1")
         .bind(&slug)
-        .fetch_one(&state.pool)
-        .await?;
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or(AppError::NotFound(ErrorCode::NovelNotFound))?;
 
     let related_id: (Uuid,) = sqlx::query_as("SELECT id FROM novels WHERE slug = $1")
         .bind(&payload.related_novel_slug)
-        .fetch_one(&state.pool)
-        .await?;
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or(AppError::NotFound(ErrorCode::NovelNotFound))?;
+
+    let mut tx = state.pool.begin().await?;
 
     // Insert the relation (ignore if already exists)
     sqlx::query(
@@ -414,40 +559,85 @@ async fn add_relation(
     .bind(novel_id.0)
     .bind(related_id.0)
     .bind(&relation_type)
-    .execute(&state.pool)
+    .execute(&mut *tx)
     .await?;
 
+    AuditEvent::new(&auth, "add_relation", "novel", novel_id.0)
+        .after(&serde_json::json!({
+            "related_novel_id": related_id.0,
+            "relation_type": relation_type,
+        }))
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
+
     Ok(StatusCode::CREATED)
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct RemoveRelationQuery {
     related_slug: String,
 }
 
 /// Remove a related novel
+#[utoipa::path(
+    delete,
+    path = "/{slug}/relations",
+    params(("slug" = String, Path, description = "Novel slug"), RemoveRelationQuery),
+    security(("bearer" = ["novels:write"])),
+    responses(
+        (status = 204, description = "Removed, or was not related"),
+        (status = 403, description = "Requires the author role"),
+    )
+)]
 async fn remove_relation(
     State(state): State<AppState>,
-    _auth: AuthUser,
+    RequireRole(auth, _): RequireRole<Author>,
+    meta: RequestMeta,
     Path(slug): Path<String>,
     Query(query): Query<RemoveRelationQuery>,
 ) -> Result<StatusCode, AppError> {
-    sqlx::query(
+    auth.require_scope(Scope::NovelsWrite)?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let removed: Option<(Uuid, Uuid, String)> = sqlx::query_as(
         "DELETE FROM novel_relations
          WHERE novel_id = (SELECT id FROM novels WHERE slug = $1)
-         AND related_novel_id = (SELECT id FROM novels WHERE slug = $2)",
+         AND related_novel_id = (SELECT id FROM novels WHERE slug = $2)
+         RETURNING novel_id, related_novel_id, relation_type",
     )
     .bind(&slug)
     .bind(&query.related_slug)
-    .execute(&state.pool)
+    .fetch_optional(&mut *tx)
     .await?;
 
+    if let Some((novel_id, related_novel_id, relation_type)) = removed {
+        AuditEvent::new(&auth, "remove_relation", "novel", novel_id)
+            .before(&serde_json::json!({
+                "related_novel_id": related_novel_id,
+                "relation_type": relation_type,
+            }))
+            .record(&mut *tx, &meta)
+            .await?;
+    }
+    tx.commit().await?;
+
     Ok(StatusCode::NO_CONTENT)
 }
 
 // Chapters endpoints
 
 /// List chapters for a novel
+#[utoipa::path(
+    get,
+    path = "/{slug}/chapters",
+    params(("slug" = String, Path, description = "Novel slug")),
+    responses(
+        (status = 200, description = "Chapters in order", body = Vec<NovelChapter>),
+    )
+)]
 async fn list_chapters(
     State(state): State<AppState>,
     Path(slug): Path<String>,
@@ -467,6 +657,15 @@ async fn list_chapters(
 }
 
 /// Get a specific chapter
+#[utoipa::path(
+    get,
+    path = "/{slug}/chapters/{number}",
+    params(("slug" = String, Path, description = "Novel slug"), ("number" = i32, Path, description = "Chapter number")),
+    responses(
+        (status = 200, body = NovelChapter),
+        (status = 404, description = "No such novel or chapter"),
+    )
+)]
 async fn get_chapter(
     State(state): State<AppState>,
     Path((slug, chapter_number)): Path<(String, i32)>,
@@ -479,19 +678,39 @@ async fn get_chapter(
     )
     .bind(&slug)
     .bind(chapter_number)
-    .fetch_one(&state.pool)
-    .await?;
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(AppError::NotFound(ErrorCode::ChapterNotFound))?;
+
+    state.metrics.view("chapter");
 
     Ok(Json(chapter))
 }
 
-/// Create a new chapter (requires authentication)
+/// Create a new chapter (requires author role)
+#[utoipa::path(
+    post,
+    path = "/{slug}/chapters",
+    params(("slug" = String, Path, description = "Novel slug")),
+    request_body = CreateChapter,
+    security(("bearer" = ["novels:write"])),
+    responses(
+        (status = 201, body = NovelChapter),
+        (status = 400, description = "Invalid chapter"),
+        (status = 403, description = "Requires the author role"),
+        (status = 404, description = "No novel with this slug"),
+        (status = 409, description = "A chapter with this number exists"),
+    )
+)]
 async fn create_chapter(
     State(state): State<AppState>,
-    _auth: AuthUser,
+    RequireRole(auth, _): RequireRole<Author>,
+    meta: RequestMeta,
     Path(slug): Path<String>,
     Json(mut payload): Json<CreateChapter>,
 ) -> Result<impl IntoResponse, AppError> {
+    auth.require_scope(Scope::NovelsWrite)?;
+
     payload.validate()?;
 
     // Sanitize content to remove null bytes
@@ -500,10 +719,12 @@ async fn create_chapter(
     // Get novel by slug
     let novel_id: (Uuid,) = sqlx::query_as("SELECT id FROM novels WHERE slug = $1")
         .bind(&slug)
-        .fetch_one(&state.pool)
-        .await?;
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or(AppError::NotFound(ErrorCode::NovelNotFound))?;
 
     let chapter_number = payload.chapter_number;
+    let mut tx = state.pool.begin().await?;
     let chapter = sqlx::query_as::<_, NovelChapter>(
         "INSERT INTO novel_chapters (novel_id, chapter_number, title, content, published_at)
          VALUES ($1, $2, $3, $4, $5)
@@ -514,27 +735,45 @@ async fn create_chapter(
     .bind(&payload.title)
     .bind(&payload.content)
     .bind(payload.published_at)
-    .fetch_one(&state.pool)
-    .await
-    .map_err(|e| {
-        if let sqlx::Error::Database(ref db_err) = e {
-            if db_err.code().as_deref() == Some("23505") {
-                return AppError::Conflict(format!("Chapter {} already exists", chapter_number));
-            }
-        }
-        e.into()
-    })?;
+    .fetch_one(&mut *tx)
+    .await?;
+
+    AuditEvent::new(&auth, "create", "chapter", chapter.id)
+        .after(&chapter)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
+
+    if chapter.published_at.is_some() {
+        state.metrics.chapter_published();
+    }
 
     Ok((StatusCode::CREATED, Json(chapter)))
 }
 
-/// Update a chapter (requires authentication)
+/// Update a chapter (requires author role)
+#[utoipa::path(
+    put,
+    path = "/{slug}/chapters/{number}",
+    params(("slug" = String, Path, description = "Novel slug"), ("number" = i32, Path, description = "Chapter number")),
+    request_body = UpdateChapter,
+    security(("bearer" = ["novels:write"])),
+    responses(
+        (status = 200, body = NovelChapter),
+        (status = 400, description = "Invalid or empty update"),
+        (status = 403, description = "Requires the author role"),
+        (status = 404, description = "No such novel or chapter"),
+    )
+)]
 async fn update_chapter(
     State(state): State<AppState>,
-    _auth: AuthUser,
+    RequireRole(auth, _): RequireRole<Author>,
+    meta: RequestMeta,
     Path((slug, chapter_number)): Path<(String, i32)>,
     Json(mut payload): Json<UpdateChapter>,
 ) -> Result<impl IntoResponse, AppError> {
+    auth.require_scope(Scope::NovelsWrite)?;
+
     // Sanitize content to remove null bytes if present
     if let Some(ref mut content) = payload.content {
         *content = content.replace('\0', "");
@@ -553,7 +792,7 @@ async fn update_chapter(
     }
 
     if updates.is_empty() {
-        return Err(AppError::BadRequest("No fields to update".to_string()));
+        return Err(AppError::BadRequest(ErrorCode::NoFieldsToUpdate));
     }
 
     updates.push("updated_at = NOW()".to_string());
@@ -578,25 +817,71 @@ async fn update_chapter(
 
     query = query.bind(&slug).bind(chapter_number);
 
-    let chapter = query.fetch_one(&state.pool).await?;
+    let mut tx = state.pool.begin().await?;
+
+    let before = sqlx::query_as::<_, NovelChapter>(
+        "SELECT c.id, c.novel_id, c.chapter_number, c.title, c.content, c.view_count, c.published_at, c.created_at, c.updated_at
+         FROM novel_chapters c
+         JOIN novels n ON c.novel_id = n.id
+         WHERE n.slug = $1 AND c.chapter_number = $2
+         FOR UPDATE OF c",
+    )
+    .bind(&slug)
+    .bind(chapter_number)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::NotFound(ErrorCode::ChapterNotFound))?;
+
+    let chapter = query.fetch_one(&mut *tx).await?;
+
+    AuditEvent::new(&auth, "update", "chapter", chapter.id)
+        .before(&before)
+        .after(&chapter)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
 
     Ok(Json(chapter))
 }
 
-/// Delete a chapter (requires authentication)
+/// Delete a chapter (requires editor role)
+#[utoipa::path(
+    delete,
+    path = "/{slug}/chapters/{number}",
+    params(("slug" = String, Path, description = "Novel slug"), ("number" = i32, Path, description = "Chapter number")),
+    security(("bearer" = ["novels:write"])),
+    responses(
+        (status = 204, description = "Deleted, or did not exist"),
+        (status = 403, description = "Requires the editor role"),
+    )
+)]
 async fn delete_chapter(
     State(state): State<AppState>,
-    _auth: AuthUser,
+    RequireRole(auth, _): RequireRole<Editor>,
+    meta: RequestMeta,
     Path((slug, chapter_number)): Path<(String, i32)>,
 ) -> Result<StatusCode, AppError> {
-    sqlx::query(
+    auth.require_scope(Scope::NovelsWrite)?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let deleted = sqlx::query_as::<_, NovelChapter>(
         "DELETE FROM novel_chapters
-         WHERE novel_id = (SELECT id FROM novels WHERE slug = $1) AND chapter_number = $2",
+         WHERE novel_id = (SELECT id FROM novels WHERE slug = $1) AND chapter_number = $2
+         RETURNING id, novel_id, chapter_number, title, content, view_count, published_at, created_at, updated_at",
     )
     .bind(&slug)
     .bind(chapter_number)
-    .execute(&state.pool)
+    .fetch_optional(&mut *tx)
     .await?;
 
+    if let Some(chapter) = deleted {
+        AuditEvent::new(&auth, "delete", "chapter", chapter.id)
+            .before(&chapter)
+            .record(&mut *tx, &meta)
+            .await?;
+    }
+    tx.commit().await?;
+
     Ok(StatusCode::NO_CONTENT)
 }
@@ -0,0 +1,164 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{delete, get},
+    Router,
+};
+use chrono::Utc;
+use utoipa::OpenApi;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    auth::tokens::{generate_api_token, hash_token},
+    db::{audit::AuditEvent, AppState},
+    error::{AppError, ErrorCode},
+    middleware::{
+        auth::AuthUser,
+        extract::{Json, Path},
+        request_meta::RequestMeta,
+    },
+    models::{ApiToken, CreateApiToken, CreatedApiToken},
+};
+
+/// Characters of the token kept in the clear to identify it in listings
+const TOKEN_PREFIX_LEN: usize = 12;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_tokens).post(create_token))
+        .route("/:id", delete(revoke_token))
+}
+
+#[derive(OpenApi)]
+#[openapi(paths(list_tokens, create_token, revoke_token))]
+pub struct TokensApi;
+
+/// List the caller's personal access tokens
+#[utoipa::path(
+    get,
+    path = "/",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Tokens, newest first", body = Vec<ApiToken>),
+        (status = 403, description = "Requires a login session, not an API token"),
+    )
+)]
+async fn list_tokens(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> Result<impl IntoResponse, AppError> {
+    auth.require_session()?;
+
+    let tokens = sqlx::query_as::<_, ApiToken>(
+        "SELECT id, name, token_prefix, scopes, last_used_at, expires_at, revoked_at, created_at
+         FROM api_tokens WHERE admin_id = $1 ORDER BY created_at DESC",
+    )
+    .bind(auth.user_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(tokens))
+}
+
+/// Create a personal access token; the secret is only shown in this response
+#[utoipa::path(
+    post,
+    path = "/",
+    request_body = CreateApiToken,
+    security(("bearer" = [])),
+    responses(
+        (status = 201, body = CreatedApiToken),
+        (status = 400, description = "Invalid token request, or expiry in the past"),
+        (status = 403, description = "Requires a login session, not an API token"),
+    )
+)]
+async fn create_token(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    meta: RequestMeta,
+    Json(payload): Json<CreateApiToken>,
+) -> Result<impl IntoResponse, AppError> {
+    // Tokens can't mint further tokens
+    auth.require_session()?;
+    payload.validate()?;
+
+    if payload
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= Utc::now())
+    {
+        return Err(AppError::BadRequest(ErrorCode::ApiTokenExpiryInPast));
+    }
+
+    let mut scopes: Vec<String> = payload.scopes.iter().map(|s| s.to_string()).collect();
+    scopes.sort();
+    scopes.dedup();
+
+    let token = generate_api_token();
+
+    let mut tx = state.pool.begin().await?;
+    let info = sqlx::query_as::<_, ApiToken>(
+        "INSERT INTO api_tokens (admin_id, name, token_prefix, token_hash, scopes, expires_at)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         RETURNING id, name, token_prefix, scopes, last_used_at, expires_at, revoked_at, created_at",
+    )
+    .bind(auth.user_id)
+    .bind(&payload.name)
+    .bind(&token[..TOKEN_PREFIX_LEN])
+    .bind(hash_token(&token))
+    .bind(&scopes)
+    .bind(payload.expires_at)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    AuditEvent::new(&auth, "create", "api_token", info.id)
+        .after(&info)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(CreatedApiToken { token, info })))
+}
+
+/// Revoke one of the caller's personal access tokens
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    params(("id" = Uuid, Path, description = "Token id")),
+    security(("bearer" = [])),
+    responses(
+        (status = 204, description = "Revoked"),
+        (status = 403, description = "Requires a login session, not an API token"),
+        (status = 404, description = "No such token of the caller's"),
+    )
+)]
+async fn revoke_token(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    meta: RequestMeta,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    auth.require_session()?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let token = sqlx::query_as::<_, ApiToken>(
+        "UPDATE api_tokens SET revoked_at = COALESCE(revoked_at, NOW())
+         WHERE id = $1 AND admin_id = $2
+         RETURNING id, name, token_prefix, scopes, last_used_at, expires_at, revoked_at, created_at",
+    )
+    .bind(id)
+    .bind(auth.user_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound(ErrorCode::ApiTokenNotFound))?;
+
+    AuditEvent::new(&auth, "revoke", "api_token", token.id)
+        .after(&token)
+        .record(&mut *tx, &meta)
+        .await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,15 @@
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Json, Router};
+
+use crate::db::AppState;
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/jwks.json", get(jwks))
+}
+
+/// Public keys for verifying access tokens; empty while tokens are signed with HS256
+async fn jwks(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(state.jwt_keys.jwks()),
+    )
+}
@@ -0,0 +1,417 @@
+//! Logging and OpenTelemetry trace export.
+//!
+//! Logs always go to stdout, as text or as the flat JSON lines described in
+//! [`json`]. When `tracing.otlp_endpoint` is set, spans are
+//! also exported over OTLP/HTTP: every request gets a server span (continuing
+//! the caller's trace when it sends a W3C `traceparent`), and every sqlx
+//! statement issued while handling it becomes a client child span carrying
+//! the statement with its literals removed.
+
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{HeaderMap, Response},
+};
+use opentelemetry::{
+    global,
+    propagation::Extractor,
+    trace::{Span as _, SpanKind, TraceContextExt as _, Tracer, TracerProvider as _},
+    KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    propagation::TraceContextPropagator,
+    trace::{Sampler, SdkTracerProvider},
+    Resource,
+};
+use std::{
+    fmt as std_fmt,
+    time::{Duration, SystemTime},
+};
+use tower_http::trace::{DefaultOnResponse, OnResponse};
+use tracing::{
+    field::{Empty, Field, Visit},
+    level_filters::LevelFilter,
+    Event, Span, Subscriber,
+};
+use tracing_opentelemetry::{OpenTelemetrySpanExt, OtelData, PreSampledTracer};
+use tracing_subscriber::{
+    filter::Targets,
+    fmt::format::JsonFields,
+    layer::{Context, SubscriberExt},
+    registry::LookupSpan,
+    util::SubscriberInitExt,
+    EnvFilter, Layer, Registry,
+};
+
+use crate::{
+    config::{Config, LogFormat},
+    middleware::request_id::RequestId,
+};
+
+mod json;
+
+/// Longest statement attached to a query span
+const MAX_STATEMENT_LEN: usize = 2048;
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Flushes exported spans when dropped; keep it alive until shutdown
+pub struct Telemetry {
+    provider: Option<SdkTracerProvider>,
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Some(provider) = self.provider.take() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Failed to flush traces: {}", e);
+            }
+        }
+    }
+}
+
+/// Install the global subscriber
+pub fn init(config: &Config) -> anyhow::Result<Telemetry> {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&config.log.filter));
+    let mut layers: Vec<BoxedLayer> = vec![match config.log.format {
+        LogFormat::Pretty => tracing_subscriber::fmt::layer().with_filter(filter).boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .fmt_fields(JsonFields::new())
+            .event_format(json::JsonFormat)
+            .with_filter(filter)
+            .boxed(),
+    }];
+
+    let provider = match &config.tracing.otlp_endpoint {
+        Some(endpoint) => {
+            let exporter = opentelemetry_otlp::SpanExporter::builder()
+                .with_http()
+                .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+                .build()?;
+
+            let provider = SdkTracerProvider::builder()
+                .with_batch_exporter(exporter)
+                .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                    config.tracing.sample_ratio,
+                ))))
+                .with_resource(
+                    Resource::builder()
+                        .with_service_name(config.tracing.service_name.clone())
+                        .with_attribute(KeyValue::new("service.version", env!("CARGO_PKG_VERSION")))
+                        .build(),
+                )
+                .build();
+
+            global::set_text_map_propagator(TraceContextPropagator::new());
+            layers.extend(export_layers(&provider));
+            Some(provider)
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry().with(layers).init();
+
+    if let Some(endpoint) = &config.tracing.otlp_endpoint {
+        tracing::info!("Exporting traces to {}", endpoint);
+    }
+
+    Ok(Telemetry { provider })
+}
+
+/// Layers turning our spans and sqlx's statement events into exported spans.
+/// They filter independently of `log.filter`, so traces don't depend on the
+/// log level.
+fn export_layers(provider: &SdkTracerProvider) -> Vec<BoxedLayer> {
+    let tracer = provider.tracer("backend");
+    let spans = Targets::new().with_target("backend", LevelFilter::INFO);
+    let queries = spans.clone().with_target("sqlx::query", LevelFilter::DEBUG);
+
+    vec![
+        tracing_opentelemetry::layer()
+            .with_tracer(tracer.clone())
+            .with_filter(spans)
+            .boxed(),
+        QuerySpans { tracer }.with_filter(queries).boxed(),
+    ]
+}
+
+/// Span for the trace layer; its fields become the server span's attributes
+pub fn make_span(request: &Request) -> Span {
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|RequestId(id)| id.as_str())
+        .unwrap_or_default();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(MatchedPath::as_str);
+
+    let span = tracing::info_span!(
+        "request",
+        method = %request.method(),
+        uri = %request.uri(),
+        version = ?request.version(),
+        request_id = %request_id,
+        http.route = route,
+        http.response.status_code = Empty,
+        otel.name = %format!("{} {}", request.method(), route.unwrap_or("unmatched")),
+        otel.kind = "server",
+        otel.status_code = Empty,
+    );
+
+    // A no-op unless export is enabled
+    let parent = global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(request.headers()))
+    });
+    span.set_parent(parent);
+
+    span
+}
+
+/// Record the status on the request span, then log like the default
+pub fn on_response<B>(response: &Response<B>, latency: Duration, span: &Span) {
+    span.record("http.response.status_code", response.status().as_u16());
+    if response.status().is_server_error() {
+        span.record("otel.status_code", "ERROR");
+    }
+
+    DefaultOnResponse::default().on_response(response, latency, span);
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|name| name.as_str()).collect()
+    }
+}
+
+/// Exports each `sqlx::query` event as a child span of the span it was
+/// logged in. Statements run outside any span (migrations, background
+/// tasks) are not exported.
+struct QuerySpans<T> {
+    tracer: T,
+}
+
+impl<S, T> Layer<S> for QuerySpans<T>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    T: Tracer + PreSampledTracer + 'static,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        if event.metadata().target() != "sqlx::query" {
+            return;
+        }
+
+        let Some(span) = ctx.event_span(event) else {
+            return;
+        };
+        let parent = match span.extensions_mut().get_mut::<OtelData>() {
+            Some(data) => self.tracer.sampled_context(data),
+            None => return,
+        };
+        if !parent.span().span_context().is_sampled() {
+            return;
+        }
+
+        let mut query = QueryFields::default();
+        event.record(&mut query);
+
+        // sqlx leaves the statement empty when the summary already is the whole of it
+        let statement = sanitize_sql(if query.statement.trim().is_empty() {
+            &query.summary
+        } else {
+            &query.statement
+        });
+        let operation = statement
+            .split_whitespace()
+            .next()
+            .unwrap_or("QUERY")
+            .to_ascii_uppercase();
+
+        let end = SystemTime::now();
+        let elapsed = Duration::try_from_secs_f64(query.elapsed_secs).unwrap_or_default();
+
+        let mut otel_span = self
+            .tracer
+            .span_builder(operation.clone())
+            .with_kind(SpanKind::Client)
+            .with_start_time(end.checked_sub(elapsed).unwrap_or(end))
+            .with_attributes([
+                KeyValue::new("db.system.name", "postgresql"),
+                KeyValue::new("db.operation.name", operation),
+                KeyValue::new("db.query.text", statement),
+                KeyValue::new("db.response.returned_rows", query.rows_returned as i64),
+            ])
+            .start_with_context(&self.tracer, &parent);
+        otel_span.end_with_timestamp(end);
+    }
+}
+
+/// Fields of sqlx's statement event
+#[derive(Default)]
+struct QueryFields {
+    summary: String,
+    statement: String,
+    rows_returned: u64,
+    elapsed_secs: f64,
+}
+
+impl Visit for QueryFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "summary" => self.summary = value.to_string(),
+            "db.statement" => self.statement = value.to_string(),
+            _ => {}
+        }
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "rows_returned" {
+            self.rows_returned = value;
+        }
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == "elapsed_secs" {
+            self.elapsed_secs = value;
+        }
+    }
+
+    fn record_debug(&mut self, _: &Field, _: &dyn std_fmt::Debug) {}
+}
+
+/// Collapse whitespace and replace string and number literals with `?`, so
+/// exported statements never carry data. Bind parameters (`$1`) are kept.
+fn sanitize_sql(sql: &str) -> String {
+    let mut sanitized = String::with_capacity(sql.len().min(MAX_STATEMENT_LEN));
+    let mut chars = sql.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if sanitized.len() >= MAX_STATEMENT_LEN {
+            sanitized.push('…');
+            break;
+        }
+
+        match c {
+            '\'' => {
+                // '' is an escaped quote inside the literal
+                while let Some(c) = chars.next() {
+                    if c == '\'' && chars.next_if_eq(&'\'').is_none() {
+                        break;
+                    }
+                }
+                sanitized.push('?');
+            }
+            c if c.is_ascii_digit()
+                && !sanitized
+                    .chars()
+                    .next_back()
+                    .is_some_and(|prev| prev.is_alphanumeric() || prev == '_' || prev == '$') =>
+            {
+                while chars.next_if(|c| c.is_ascii_digit() || *c == '.').is_some() {}
+                sanitized.push('?');
+            }
+            c if c.is_whitespace() => {
+                if !sanitized.is_empty() && !sanitized.ends_with(' ') {
+                    sanitized.push(' ');
+                }
+            }
+            c => sanitized.push(c),
+        }
+    }
+
+    sanitized.truncate(sanitized.trim_end().len());
+    sanitized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use opentelemetry::trace::{SpanId, TraceId};
+    use opentelemetry_sdk::trace::InMemorySpanExporter;
+
+    #[test]
+    fn sanitize_removes_literals() {
+        assert_eq!(
+            sanitize_sql("SELECT *\n  FROM novels\n  WHERE slug = 'it''s' AND id > 42 LIMIT $1"),
+            "SELECT * FROM novels WHERE slug = ? AND id > ? LIMIT $1"
+        );
+        assert_eq!(
+            sanitize_sql("SELECT t1.version FROM _sqlx_migrations t1 WHERE x = 1.5"),
+            "SELECT t1.version FROM _sqlx_migrations t1 WHERE x = ?"
+        );
+    }
+
+    #[test]
+    fn sanitize_truncates_long_statements() {
+        let sql = format!("SELECT {}", "a, ".repeat(MAX_STATEMENT_LEN));
+        assert!(sanitize_sql(&sql).chars().count() <= MAX_STATEMENT_LEN + 1);
+    }
+
+    #[test]
+    fn exports_request_and_query_spans() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry().with(export_layers(&provider));
+
+        let request = Request::builder()
+            .uri("/api/novels/some-novel")
+            .header(
+                "traceparent",
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            )
+            .body(Body::empty())
+            .unwrap();
+
+        tracing::subscriber::with_default(subscriber, || {
+            make_span(&request).in_scope(|| {
+                tracing::debug!(
+                    target: "sqlx::query",
+                    summary = "SELECT id FROM novels WHERE slug = 'some-novel'",
+                    db.statement = "",
+                    rows_affected = 0u64,
+                    rows_returned = 1u64,
+                    elapsed_secs = 0.002,
+                );
+            });
+        });
+        provider.force_flush().unwrap();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let request_span = spans
+            .iter()
+            .find(|span| span.name == "GET unmatched")
+            .unwrap();
+        let query_span = spans.iter().find(|span| span.name == "SELECT").unwrap();
+
+        let trace_id = TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap();
+        assert_eq!(request_span.span_context.trace_id(), trace_id);
+        assert_eq!(
+            request_span.parent_span_id,
+            SpanId::from_hex("00f067aa0ba902b7").unwrap()
+        );
+        assert_eq!(request_span.span_kind, SpanKind::Server);
+
+        assert_eq!(query_span.span_context.trace_id(), trace_id);
+        assert_eq!(
+            query_span.parent_span_id,
+            request_span.span_context.span_id()
+        );
+        assert_eq!(query_span.span_kind, SpanKind::Client);
+        assert!(query_span.attributes.contains(&KeyValue::new(
+            "db.query.text",
+            "SELECT id FROM novels WHERE slug = ?"
+        )));
+    }
+}
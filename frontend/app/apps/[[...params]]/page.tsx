@@ -15,7 +15,7 @@ export async function generateStaticParams(): Promise<
   const index = { params: [] as string[] };
 
   try {
-    const response = await fetch(`${API_BASE_URL}/api/apps?limit=100`, {
+    const response = await fetch(`${API_BASE_URL}/api/v1/apps?limit=100`, {
       next: { revalidate: 3600 },
     });
 
@@ -62,7 +62,7 @@ export async function generateMetadata({
   }
 
   try {
-    const response = await fetch(`${API_BASE_URL}/api/apps/${slug}`, {
+    const response = await fetch(`${API_BASE_URL}/api/v1/apps/${slug}`, {
       next: { revalidate: 3600 },
     });
 
@@ -19,7 +19,7 @@ export async function generateStaticParams(): Promise<
 
   try {
     const response = await fetch(
-      `${API_BASE_URL}/api/blog?published=true&limit=100`,
+      `${API_BASE_URL}/api/v1/blog?published=true&limit=100`,
       { next: { revalidate: 3600 } }
     );
 
@@ -66,7 +66,7 @@ export async function generateMetadata({
   }
 
   try {
-    const response = await fetch(`${API_BASE_URL}/api/blog/${slug}`, {
+    const response = await fetch(`${API_BASE_URL}/api/v1/blog/${slug}`, {
       next: { revalidate: 3600 },
     });
 
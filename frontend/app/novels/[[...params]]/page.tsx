@@ -23,11 +23,11 @@ export async function generateStaticParams(): Promise<
     // We'll try to fetch a reasonably large number or use pagination if needed.
     // For SSG, we ideally want everything. Let's assume a large limit covers it for now.
     const response = await fetch(
-      `${API_BASE_URL}/api/novels?limit=1000&status=ongoing`, // Fetch ongoing
+      `${API_BASE_URL}/api/v1/novels?limit=1000&status=ongoing`, // Fetch ongoing
       { next: { revalidate: 3600 } }
     );
     const responseCompleted = await fetch(
-      `${API_BASE_URL}/api/novels?limit=1000&status=completed`, // Fetch completed
+      `${API_BASE_URL}/api/v1/novels?limit=1000&status=completed`, // Fetch completed
       { next: { revalidate: 3600 } }
     );
 
@@ -56,7 +56,7 @@ export async function generateStaticParams(): Promise<
       // Fetch chapters for this novel
       try {
         const chaptersResponse = await fetch(
-          `${API_BASE_URL}/api/novels/${novel.slug}/chapters`,
+          `${API_BASE_URL}/api/v1/novels/${novel.slug}/chapters`,
           { next: { revalidate: 3600 } }
         );
 
@@ -107,7 +107,7 @@ export async function generateMetadata({
 
   try {
     // Fetch Novel Data
-    const novelResponse = await fetch(`${API_BASE_URL}/api/novels/${slug}`, {
+    const novelResponse = await fetch(`${API_BASE_URL}/api/v1/novels/${slug}`, {
       next: { revalidate: 3600 },
     });
 
@@ -136,7 +136,7 @@ export async function generateMetadata({
 
     // 3. /novels/[slug]/[chapter] (Chapter)
     const chapterResponse = await fetch(
-      `${API_BASE_URL}/api/novels/${slug}/chapters/${chapterNum}`,
+      `${API_BASE_URL}/api/v1/novels/${slug}/chapters/${chapterNum}`,
       {
         next: { revalidate: 3600 },
       }
@@ -51,7 +51,7 @@ export default async function sitemap(): Promise<MetadataRoute.Sitemap> {
   // Fetch all published blog posts
   try {
     const blogResponse = await fetch(
-      `${API_BASE_URL}/api/blog?published=true&limit=1000`,
+      `${API_BASE_URL}/api/v1/blog?published=true&limit=1000`,
       {
         next: { revalidate: 3600 },
       }
@@ -77,11 +77,11 @@ export default async function sitemap(): Promise<MetadataRoute.Sitemap> {
   // Fetch all published novels and their chapters
   try {
     const ongoingResponse = await fetch(
-      `${API_BASE_URL}/api/novels?limit=1000&status=ongoing`,
+      `${API_BASE_URL}/api/v1/novels?limit=1000&status=ongoing`,
       { next: { revalidate: 3600 } }
     );
     const completedResponse = await fetch(
-      `${API_BASE_URL}/api/novels?limit=1000&status=completed`,
+      `${API_BASE_URL}/api/v1/novels?limit=1000&status=completed`,
       { next: { revalidate: 3600 } }
     );
 
@@ -116,7 +116,7 @@ export default async function sitemap(): Promise<MetadataRoute.Sitemap> {
       // Novel Chapters
       try {
         const chaptersResponse = await fetch(
-          `${API_BASE_URL}/api/novels/${novel.slug}/chapters`,
+          `${API_BASE_URL}/api/v1/novels/${novel.slug}/chapters`,
           { next: { revalidate: 3600 } }
         );
 
@@ -27,10 +27,11 @@ async function apiCall<T>(
   const response = await fetch(`${API_BASE_URL}${endpoint}`, config);
 
   if (!response.ok) {
-    const error = await response.json().catch(() => ({
-      error: `HTTP ${response.status}`,
+    // Errors are RFC 7807 problem details; see backend/src/error/code.rs
+    const problem = await response.json().catch(() => ({
+      detail: `HTTP ${response.status}`,
     }));
-    throw new Error(error.error || "API request failed");
+    throw new Error(problem.detail || "API request failed");
   }
 
   // Handle empty response (e.g., DELETE requests)
@@ -44,10 +45,10 @@ async function apiCall<T>(
 // Auth API
 export const authApi = {
   login: (credentials: { username: string; password: string }) =>
-    apiCall("POST", "/api/auth/login", credentials),
+    apiCall("POST", "/api/v1/auth/login", credentials),
 
   register: (credentials: { username: string; password: string }) =>
-    apiCall("POST", "/api/auth/register", credentials),
+    apiCall("POST", "/api/v1/auth/register", credentials),
 };
 
 // Novels API
@@ -68,59 +69,59 @@ export const novelsApi = {
     if (params?.offset) query.append("offset", params.offset.toString());
     if (params?.include_drafts) query.append("include_drafts", "true");
     const queryString = query.toString();
-    return apiCall("GET", `/api/novels${queryString ? `?${queryString}` : ""}`);
+    return apiCall("GET", `/api/v1/novels${queryString ? `?${queryString}` : ""}`);
   },
 
-  getBySlug: (slug: string) => apiCall("GET", `/api/novels/${slug}`),
+  getBySlug: (slug: string) => apiCall("GET", `/api/v1/novels/${slug}`),
 
   create: (data: unknown, token: string) =>
-    apiCall("POST", "/api/novels", data, token),
+    apiCall("POST", "/api/v1/novels", data, token),
 
   update: (slug: string, data: unknown, token: string) =>
-    apiCall("PUT", `/api/novels/${slug}`, data, token),
+    apiCall("PUT", `/api/v1/novels/${slug}`, data, token),
 
   delete: (slug: string, token: string) =>
-    apiCall("DELETE", `/api/novels/${slug}`, undefined, token),
+    apiCall("DELETE", `/api/v1/novels/${slug}`, undefined, token),
 
   // Genres and Types
-  getGenres: () => apiCall("GET", "/api/novels/genres"),
+  getGenres: () => apiCall("GET", "/api/v1/novels/genres"),
 
-  getNovelTypes: () => apiCall("GET", "/api/novels/types"),
+  getNovelTypes: () => apiCall("GET", "/api/v1/novels/types"),
 
   // Related novels
   getRelations: (slug: string) =>
-    apiCall("GET", `/api/novels/${slug}/relations`),
+    apiCall("GET", `/api/v1/novels/${slug}/relations`),
 
   addRelation: (
     slug: string,
     data: { related_novel_slug: string; relation_type?: string },
     token: string
-  ) => apiCall("POST", `/api/novels/${slug}/relations`, data, token),
+  ) => apiCall("POST", `/api/v1/novels/${slug}/relations`, data, token),
 
   removeRelation: (slug: string, relatedSlug: string, token: string) =>
     apiCall(
       "DELETE",
-      `/api/novels/${slug}/relations?related_slug=${relatedSlug}`,
+      `/api/v1/novels/${slug}/relations?related_slug=${relatedSlug}`,
       undefined,
       token
     ),
 
   // Chapters
-  getChapters: (slug: string) => apiCall("GET", `/api/novels/${slug}/chapters`),
+  getChapters: (slug: string) => apiCall("GET", `/api/v1/novels/${slug}/chapters`),
 
   getChapter: (slug: string, number: number) =>
-    apiCall("GET", `/api/novels/${slug}/chapters/${number}`),
+    apiCall("GET", `/api/v1/novels/${slug}/chapters/${number}`),
 
   createChapter: (slug: string, data: unknown, token: string) =>
-    apiCall("POST", `/api/novels/${slug}/chapters`, data, token),
+    apiCall("POST", `/api/v1/novels/${slug}/chapters`, data, token),
 
   updateChapter: (slug: string, number: number, data: unknown, token: string) =>
-    apiCall("PUT", `/api/novels/${slug}/chapters/${number}`, data, token),
+    apiCall("PUT", `/api/v1/novels/${slug}/chapters/${number}`, data, token),
 
   deleteChapter: (slug: string, number: number, token: string) =>
     apiCall(
       "DELETE",
-      `/api/novels/${slug}/chapters/${number}`,
+      `/api/v1/novels/${slug}/chapters/${number}`,
       undefined,
       token
     ),
@@ -135,19 +136,19 @@ export const blogApi = {
     if (params?.limit) query.append("limit", params.limit.toString());
     if (params?.offset) query.append("offset", params.offset.toString());
     const queryString = query.toString();
-    return apiCall("GET", `/api/blog${queryString ? `?${queryString}` : ""}`);
+    return apiCall("GET", `/api/v1/blog${queryString ? `?${queryString}` : ""}`);
   },
 
-  getBySlug: (slug: string) => apiCall("GET", `/api/blog/${slug}`),
+  getBySlug: (slug: string) => apiCall("GET", `/api/v1/blog/${slug}`),
 
   create: (data: unknown, token: string) =>
-    apiCall("POST", "/api/blog", data, token),
+    apiCall("POST", "/api/v1/blog", data, token),
 
   update: (slug: string, data: unknown, token: string) =>
-    apiCall("PUT", `/api/blog/${slug}`, data, token),
+    apiCall("PUT", `/api/v1/blog/${slug}`, data, token),
 
   delete: (slug: string, token: string) =>
-    apiCall("DELETE", `/api/blog/${slug}`, undefined, token),
+    apiCall("DELETE", `/api/v1/blog/${slug}`, undefined, token),
 };
 
 // Apps API
@@ -158,25 +159,25 @@ export const appsApi = {
     if (params?.limit) query.append("limit", params.limit.toString());
     if (params?.offset) query.append("offset", params.offset.toString());
     const queryString = query.toString();
-    return apiCall("GET", `/api/apps${queryString ? `?${queryString}` : ""}`);
+    return apiCall("GET", `/api/v1/apps${queryString ? `?${queryString}` : ""}`);
   },
 
-  getBySlug: (slug: string) => apiCall("GET", `/api/apps/${slug}`),
+  getBySlug: (slug: string) => apiCall("GET", `/api/v1/apps/${slug}`),
 
   // Get available platforms
-  getPlatforms: () => apiCall("GET", "/api/apps/platforms"),
+  getPlatforms: () => apiCall("GET", "/api/v1/apps/platforms"),
 
   // Get available distribution channels
-  getChannels: () => apiCall("GET", "/api/apps/channels"),
+  getChannels: () => apiCall("GET", "/api/v1/apps/channels"),
 
   create: (data: unknown, token: string) =>
-    apiCall("POST", "/api/apps", data, token),
+    apiCall("POST", "/api/v1/apps", data, token),
 
   update: (slug: string, data: unknown, token: string) =>
-    apiCall("PUT", `/api/apps/${slug}`, data, token),
+    apiCall("PUT", `/api/v1/apps/${slug}`, data, token),
 
   delete: (slug: string, token: string) =>
-    apiCall("DELETE", `/api/apps/${slug}`, undefined, token),
+    apiCall("DELETE", `/api/v1/apps/${slug}`, undefined, token),
 };
 
 // System API